@@ -0,0 +1,356 @@
+use std::{marker::PhantomData, sync::Arc, time::Duration};
+
+use nertsal_commands::CommandNode;
+
+use crate::types::*;
+
+/// Type of a command argument, that is checked before the command is called
+#[derive(Debug, Clone)]
+pub enum ArgumentKind {
+    /// A single word
+    Word,
+    /// The rest of the message, should be the last argument
+    Line,
+    Integer {
+        min: Option<i64>,
+        max: Option<i64>,
+    },
+    /// Time in the `hh:mm:ss` format, hours and minutes are optional
+    Duration,
+    /// A user name, with or without `@`
+    User,
+    /// An http or https link
+    Url,
+    /// One of the given words, ignoring the case
+    Choice(Vec<String>),
+}
+
+/// Parsed value of a command argument
+#[derive(Debug, Clone)]
+pub enum ArgumentValue {
+    Text(String),
+    Integer(i64),
+    Duration(Duration),
+    User(UserRef),
+}
+
+/// Values of the typed arguments, `None` for the omitted optional ones
+#[derive(Debug, Clone)]
+pub struct Arguments(pub Vec<Option<ArgumentValue>>);
+
+#[derive(Debug, Clone)]
+pub struct ArgumentSpec {
+    pub name: String,
+    pub kind: ArgumentKind,
+    pub optional: bool,
+}
+
+/// Path of a command and the arguments it expects
+#[derive(Debug, Clone)]
+pub struct Signature {
+    pub path: Vec<String>,
+    pub arguments: Vec<ArgumentSpec>,
+}
+
+/// Node, that carries the usage of a typed command, e.g. `!timer countdown [hh:mm:ss]`.
+/// The command tree only knows that the arguments are matched as a single line,
+/// so the usage is kept next to the command for the help.
+/// It has no literals, so no message can ever match it.
+fn usage_node<T>(usage: String) -> CommandNode<T, Sender, Response> {
+    CommandNode::Literal {
+        literals: Vec::new(),
+        child_nodes: vec![CommandNode::ArgumentChoice {
+            choices: vec![usage],
+            child_nodes: Vec::new(),
+        }],
+    }
+}
+
+/// Usage of the typed command, if the node was made by [usage_node]
+pub(crate) fn typed_usage<T>(node: &CommandNode<T, Sender, Response>) -> Option<&str> {
+    match node {
+        CommandNode::Literal {
+            literals,
+            child_nodes,
+        } if literals.is_empty() => match child_nodes.as_slice() {
+            [CommandNode::ArgumentChoice { choices, .. }] => choices.first().map(String::as_str),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Builder of a command with typed arguments.
+/// Everything after the command path is matched as a single line and checked against the arguments.
+/// If the check fails, the caller is answered with the usage of the command,
+/// e.g. `Usage: !timer countdown [hh:mm:ss]`.
+pub struct TypedCommand<T> {
+    signature: Signature,
+    bot: PhantomData<fn(&mut T)>,
+}
+
+impl<T: 'static> TypedCommand<T> {
+    /// Creates a command with the path separated by spaces, e.g. `!timer countdown`
+    pub fn new(path: &str) -> Self {
+        Self {
+            signature: Signature {
+                path: path
+                    .split_whitespace()
+                    .map(|word| word.to_owned())
+                    .collect(),
+                arguments: Vec::new(),
+            },
+            bot: PhantomData,
+        }
+    }
+
+    pub fn argument(mut self, name: &str, kind: ArgumentKind) -> Self {
+        self.signature.arguments.push(ArgumentSpec {
+            name: name.to_owned(),
+            kind,
+            optional: false,
+        });
+        self
+    }
+
+    pub fn word(self, name: &str) -> Self {
+        self.argument(name, ArgumentKind::Word)
+    }
+
+    pub fn line(self, name: &str) -> Self {
+        self.argument(name, ArgumentKind::Line)
+    }
+
+    pub fn integer(self, name: &str) -> Self {
+        self.argument(
+            name,
+            ArgumentKind::Integer {
+                min: None,
+                max: None,
+            },
+        )
+    }
+
+    pub fn integer_range(self, name: &str, min: i64, max: i64) -> Self {
+        self.argument(
+            name,
+            ArgumentKind::Integer {
+                min: Some(min),
+                max: Some(max),
+            },
+        )
+    }
+
+    pub fn duration(self, name: &str) -> Self {
+        self.argument(name, ArgumentKind::Duration)
+    }
+
+    pub fn user(self, name: &str) -> Self {
+        self.argument(name, ArgumentKind::User)
+    }
+
+    pub fn url(self, name: &str) -> Self {
+        self.argument(name, ArgumentKind::Url)
+    }
+
+    pub fn choice(self, name: &str, choices: impl IntoIterator<Item = impl ToString>) -> Self {
+        self.argument(
+            name,
+            ArgumentKind::Choice(
+                choices
+                    .into_iter()
+                    .map(|choice| choice.to_string())
+                    .collect(),
+            ),
+        )
+    }
+
+    /// Makes the last added argument optional
+    pub fn optional(mut self) -> Self {
+        if let Some(argument) = self.signature.arguments.last_mut() {
+            argument.optional = true;
+        }
+        self
+    }
+
+    pub fn finalize(
+        self,
+        authority_level: usize,
+        command: impl Fn(&mut T, &Sender, Arguments) -> Response + Send + Sync + 'static,
+    ) -> CommandNode<T, Sender, Response> {
+        let signature = self.signature;
+        let usage = signature.words();
+        let mut builder = CommandBuilder::<T>::new();
+        for word in &signature.path {
+            builder = builder.literal([word.clone()]);
+        }
+        let command = Arc::new(move |bot: &mut T, sender: &Sender, args: Vec<String>| {
+            let line = args.first().map(|line| line.as_str()).unwrap_or("");
+            match signature.parse(line) {
+                Ok(arguments) => command(bot, sender, arguments),
+                Err(error) => Some(format!("{}. {}", error, signature.usage()).into()),
+            }
+        });
+        builder.split([
+            CommandBuilder::new().finalize(true, authority_level, command.clone()),
+            CommandBuilder::new()
+                .line()
+                .finalize(true, authority_level, command),
+            usage_node(usage),
+        ])
+    }
+}
+
+impl Signature {
+    pub fn usage(&self) -> String {
+        format!("Usage: {}", self.words())
+    }
+
+    /// Path and arguments of the command, e.g. `!timer countdown [hh:mm:ss]`
+    pub fn words(&self) -> String {
+        let mut usage = self.path.join(" ");
+        for argument in &self.arguments {
+            let name = match &argument.kind {
+                ArgumentKind::Choice(choices) => choices.join("|"),
+                _ => argument.name.clone(),
+            };
+            if argument.optional {
+                usage += &format!(" [{}]", name);
+            } else {
+                usage += &format!(" <{}>", name);
+            }
+        }
+        usage
+    }
+
+    /// Checks the text given after the command path against the arguments
+    pub fn parse(&self, line: &str) -> Result<Arguments, String> {
+        let mut rest = line.trim();
+        let mut values = Vec::with_capacity(self.arguments.len());
+        for argument in &self.arguments {
+            if rest.is_empty() {
+                if argument.optional {
+                    values.push(None);
+                    continue;
+                }
+                return Err(format!("Missing {}", argument.name));
+            }
+            let value = match argument.kind {
+                ArgumentKind::Line => std::mem::take(&mut rest),
+                _ => {
+                    let (word, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                    rest = tail.trim_start();
+                    word
+                }
+            };
+            let value = argument
+                .kind
+                .parse(value)
+                .map_err(|error| format!("Invalid {}: {}", argument.name, error))?;
+            values.push(Some(value));
+        }
+        if !rest.is_empty() {
+            return Err(format!("Unexpected {}", rest));
+        }
+        Ok(Arguments(values))
+    }
+}
+
+impl ArgumentKind {
+    pub fn parse(&self, value: &str) -> Result<ArgumentValue, String> {
+        match self {
+            Self::Word | Self::Line => Ok(ArgumentValue::Text(value.to_owned())),
+            Self::Integer { min, max } => {
+                let number = value
+                    .parse::<i64>()
+                    .map_err(|_| format!("{} is not a number", value))?;
+                match (min, max) {
+                    (Some(min), Some(max)) if number < *min || number > *max => {
+                        Err(format!("should be a number from {} to {}", min, max))
+                    }
+                    (Some(min), None) if number < *min => {
+                        Err(format!("should be at least {}", min))
+                    }
+                    (None, Some(max)) if number > *max => Err(format!("should be at most {}", max)),
+                    _ => Ok(ArgumentValue::Integer(number)),
+                }
+            }
+            Self::Duration => parse_duration(value).map(ArgumentValue::Duration),
+            Self::User => {
+                let user = UserRef::parse(value);
+                if user.name.is_empty() {
+                    Err("expected a user name".to_owned())
+                } else {
+                    Ok(ArgumentValue::User(user))
+                }
+            }
+            Self::Url => {
+                let host = value
+                    .strip_prefix("https://")
+                    .or_else(|| value.strip_prefix("http://"))
+                    .and_then(|rest| rest.split(|c| matches!(c, '/' | '?' | '#')).next());
+                match host {
+                    Some(host) if host.contains('.') => Ok(ArgumentValue::Text(value.to_owned())),
+                    _ => Err(format!("{} is not a link", value)),
+                }
+            }
+            Self::Choice(choices) => choices
+                .iter()
+                .find(|choice| choice.eq_ignore_ascii_case(value))
+                .map(|choice| ArgumentValue::Text(choice.clone()))
+                .ok_or_else(|| format!("expected one of {}", choices.join(", "))),
+        }
+    }
+}
+
+impl Arguments {
+    pub fn get(&self, index: usize) -> Option<&ArgumentValue> {
+        self.0.get(index).and_then(|value| value.as_ref())
+    }
+
+    pub fn text(&self, index: usize) -> Option<&str> {
+        match self.get(index)? {
+            ArgumentValue::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn integer(&self, index: usize) -> Option<i64> {
+        match self.get(index)? {
+            ArgumentValue::Integer(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    pub fn duration(&self, index: usize) -> Option<Duration> {
+        match self.get(index)? {
+            ArgumentValue::Duration(duration) => Some(*duration),
+            _ => None,
+        }
+    }
+
+    pub fn user(&self, index: usize) -> Option<&UserRef> {
+        match self.get(index)? {
+            ArgumentValue::User(user) => Some(user),
+            _ => None,
+        }
+    }
+}
+
+/// Parses time in the `hh:mm:ss` format, hours and minutes are optional
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let error = || format!("{} is not in the hh:mm:ss format", value);
+    let parts = value.split(':').collect::<Vec<_>>();
+    if parts.len() > 3 {
+        return Err(error());
+    }
+    let mut secs: u64 = 0;
+    for (part, multiplier) in parts.into_iter().rev().zip([1, 60, 3600]) {
+        let part = part.parse::<u64>().map_err(|_| error())?;
+        secs = part
+            .checked_mul(multiplier)
+            .and_then(|part| secs.checked_add(part))
+            .ok_or_else(|| format!("{} is too long", value))?;
+    }
+    Ok(Duration::from_secs(secs))
+}
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+use twitch_irc::message::{IRCMessage, PrivmsgMessage, UserNoticeMessage};
+
+/// Events happening in the chat, other than the plain messages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ChatEvent {
+    /// A new subscription or a resubscription
+    Subscription {
+        user: String,
+        months: u32,
+        /// `Prime`, `1000`, `2000` or `3000`
+        plan: String,
+        message: Option<String>,
+    },
+    SubGift {
+        gifter: String,
+        recipient: String,
+        plan: String,
+    },
+    Raid {
+        raider: String,
+        viewers: u64,
+    },
+    Cheer {
+        user: String,
+        bits: u64,
+        message: String,
+    },
+    /// A channel points reward, which requires the viewer to enter text
+    Redemption {
+        user: String,
+        reward_id: String,
+        message: String,
+    },
+}
+
+impl ChatEvent {
+    pub fn from_user_notice(message: &UserNoticeMessage) -> Option<Self> {
+        let source = &message.source;
+        let user = message.sender.name.clone();
+        match tag(source, "msg-id")? {
+            "sub" | "resub" => Some(Self::Subscription {
+                user,
+                months: tag(source, "msg-param-cumulative-months")
+                    .and_then(|months| months.parse().ok())
+                    .unwrap_or(1),
+                plan: tag(source, "msg-param-sub-plan")
+                    .unwrap_or_default()
+                    .to_owned(),
+                message: message.message_text.clone(),
+            }),
+            "subgift" | "anonsubgift" => Some(Self::SubGift {
+                gifter: user,
+                recipient: tag(source, "msg-param-recipient-display-name")?.to_owned(),
+                plan: tag(source, "msg-param-sub-plan")
+                    .unwrap_or_default()
+                    .to_owned(),
+            }),
+            "raid" => Some(Self::Raid {
+                raider: tag(source, "msg-param-displayName").map_or(user, |name| name.to_owned()),
+                viewers: tag(source, "msg-param-viewerCount")
+                    .and_then(|viewers| viewers.parse().ok())
+                    .unwrap_or(0),
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn from_privmsg(message: &PrivmsgMessage) -> Option<Self> {
+        let user = message.sender.name.clone();
+        if let Some(bits) = message.bits {
+            return Some(Self::Cheer {
+                user,
+                bits,
+                message: message.message_text.clone(),
+            });
+        }
+        tag(&message.source, "custom-reward-id").map(|reward_id| Self::Redemption {
+            user,
+            reward_id: reward_id.to_owned(),
+            message: message.message_text.clone(),
+        })
+    }
+
+    /// The user that caused the event
+    pub fn user(&self) -> &str {
+        match self {
+            Self::Subscription { user, .. }
+            | Self::Cheer { user, .. }
+            | Self::Redemption { user, .. } => user,
+            Self::SubGift { gifter, .. } => gifter,
+            Self::Raid { raider, .. } => raider,
+        }
+    }
+}
+
+/// Returns the value of the irc tag, if it is present and not empty
+pub fn tag<'a>(message: &'a IRCMessage, key: &str) -> Option<&'a str> {
+    message
+        .tags
+        .0
+        .get(key)
+        .and_then(|value| value.as_deref())
+        .filter(|value| !value.is_empty())
+}
@@ -0,0 +1,211 @@
+use nertsal_commands::{ArgumentType, CommandNode};
+use serde::{Deserialize, Serialize};
+
+use crate::{arguments::typed_usage, types::*};
+
+/// Description of a command, generated from the command tree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandInfo {
+    /// Words of the command, e.g. `!queue|!list` or `!timer set [text]`
+    pub usage: String,
+    pub authority_level: usize,
+}
+
+/// Command in the tree, that a message would call if the authority allowed it
+#[derive(Debug, Clone)]
+pub struct CommandMatch {
+    /// Literal words of the command, as written in the message, e.g. `!timer set`
+    pub path: String,
+    pub authority_level: usize,
+}
+
+/// Finds the commands, that the message would call regardless of the authority level
+pub fn match_commands<T>(commands: &Commands<T>, message_text: &str) -> Vec<CommandMatch> {
+    let words = message_text.split_whitespace().collect::<Vec<_>>();
+    let mut matches = Vec::new();
+    for node in &commands.commands {
+        match_node(node, &words, &[], true, &mut matches);
+    }
+    matches
+}
+
+/// Only the words before the first argument make up the path,
+/// so the path stops growing once an argument is matched
+fn match_node<T>(
+    node: &CommandNode<T, Sender, Response>,
+    words: &[&str],
+    path: &[String],
+    path_open: bool,
+    matches: &mut Vec<CommandMatch>,
+) {
+    let (child_nodes, rest, literal) = match node {
+        CommandNode::Literal {
+            literals: options,
+            child_nodes,
+        }
+        | CommandNode::ArgumentChoice {
+            choices: options,
+            child_nodes,
+        } => match words.split_first() {
+            Some((word, rest)) if options.iter().any(|option| option.as_str() == *word) => {
+                (child_nodes, rest, Some(*word))
+            }
+            _ => return,
+        },
+        CommandNode::Argument {
+            argument_type,
+            child_nodes,
+        } => {
+            let rest: &[&str] = match (argument_type, words.split_first()) {
+                (_, None) => return,
+                (ArgumentType::Word, Some((_, rest))) => rest,
+                (ArgumentType::Line, Some(_)) => &[],
+            };
+            (child_nodes, rest, None)
+        }
+        CommandNode::Final {
+            authority_level, ..
+        } => {
+            if words.is_empty() {
+                matches.push(CommandMatch {
+                    path: path.join(" "),
+                    authority_level: *authority_level,
+                });
+            }
+            return;
+        }
+    };
+    let (child_path, child_open) = match literal {
+        Some(word) if path_open => {
+            let mut child_path = path.to_vec();
+            child_path.push(word.to_owned());
+            (child_path, true)
+        }
+        _ => (path.to_vec(), false),
+    };
+    for child in child_nodes {
+        match_node(child, rest, &child_path, child_open, matches);
+    }
+}
+
+/// Lists every command in the tree
+pub fn command_infos<T>(commands: &Commands<T>) -> Vec<CommandInfo> {
+    let mut infos = Vec::new();
+    for node in &commands.commands {
+        collect_infos(node, &mut Vec::new(), &mut infos);
+    }
+    merge_optional(infos)
+}
+
+fn collect_infos<T>(
+    node: &CommandNode<T, Sender, Response>,
+    path: &mut Vec<String>,
+    infos: &mut Vec<CommandInfo>,
+) {
+    let (word, child_nodes) = match node {
+        CommandNode::Literal {
+            literals,
+            child_nodes,
+        } => (literals.join("|"), child_nodes),
+        CommandNode::ArgumentChoice {
+            choices,
+            child_nodes,
+        } => (choices.join("|"), child_nodes),
+        CommandNode::Argument {
+            argument_type,
+            child_nodes,
+        } => {
+            let word = match argument_type {
+                ArgumentType::Word => "<word>",
+                ArgumentType::Line => "<text>",
+            };
+            (word.to_owned(), child_nodes)
+        }
+        CommandNode::Final {
+            authority_level, ..
+        } => {
+            infos.push(CommandInfo {
+                usage: path.join(" "),
+                authority_level: *authority_level,
+            });
+            return;
+        }
+    };
+    // Typed commands are described by their signature instead of the line they match
+    if let Some(usage) = child_nodes.iter().find_map(typed_usage) {
+        let authority_level = child_nodes.iter().find_map(|child| match child {
+            CommandNode::Final {
+                authority_level, ..
+            } => Some(*authority_level),
+            _ => None,
+        });
+        if let Some(authority_level) = authority_level {
+            infos.push(CommandInfo {
+                usage: usage.to_owned(),
+                authority_level,
+            });
+        }
+        return;
+    }
+    path.push(word);
+    for child in child_nodes {
+        collect_infos(child, path, infos);
+    }
+    path.pop();
+}
+
+/// Joins commands like `!backup create` and `!backup create <word>`
+/// into a single command with an optional argument: `!backup create [word]`
+fn merge_optional(infos: Vec<CommandInfo>) -> Vec<CommandInfo> {
+    let mut merged: Vec<CommandInfo> = Vec::with_capacity(infos.len());
+    for info in infos {
+        let optional = info
+            .usage
+            .rsplit_once(' ')
+            .filter(|(_, last)| last.starts_with('<') && last.ends_with('>'))
+            .and_then(|(prefix, last)| {
+                merged
+                    .iter()
+                    .position(|other| {
+                        other.usage == prefix && other.authority_level == info.authority_level
+                    })
+                    .map(|index| (index, format!("{} [{}]", prefix, &last[1..last.len() - 1])))
+            });
+        match optional {
+            Some((index, usage)) => merged[index].usage = usage,
+            None => merged.push(info),
+        }
+    }
+    merged
+}
+
+impl CommandInfo {
+    /// Checks whether the command starts with the given words,
+    /// any of the alternatives (like in `!queue|!list`) can be used
+    pub fn matches(&self, query: &str) -> bool {
+        let mut words = self.usage.split_whitespace();
+        query.split_whitespace().all(|query_word| {
+            words.next().is_some_and(|word| {
+                word.split('|')
+                    .any(|alternative| alternative.eq_ignore_ascii_case(query_word))
+            })
+        })
+    }
+
+    /// Every alternative of the first word of the command, e.g. `!queue` and `!list`
+    pub fn name_alternatives(&self) -> impl Iterator<Item = &str> {
+        self.usage
+            .split_whitespace()
+            .next()
+            .unwrap_or("")
+            .split('|')
+    }
+
+    /// The first word of the command, without the alternatives
+    pub fn name(&self) -> &str {
+        self.usage
+            .split(|c: char| c.is_whitespace() || c == '|')
+            .next()
+            .unwrap_or("")
+    }
+}
@@ -1,8 +1,17 @@
+mod arguments;
+mod events;
+mod help;
 mod types;
 
+pub use arguments::*;
+pub use events::*;
+pub use help::*;
 pub use types::*;
 
 pub mod prelude {
+    pub use crate::arguments::*;
+    pub use crate::events::*;
+    pub use crate::help::*;
     pub use crate::types::{AuthorityLevel, CommandBuilder, CommandMessage, Commands, *};
     pub use async_trait::async_trait;
     pub use futures;
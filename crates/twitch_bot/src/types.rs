@@ -14,12 +14,76 @@ pub type Response = Option<ResponseMsg>;
 pub struct ResponseMsg {
     pub message: String,
     pub send_to_twitch: bool,
+    #[serde(default)]
+    pub delivery: Delivery,
+}
+
+/// How the response is delivered to twitch
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub enum Delivery {
+    /// A plain message in the channel
+    #[default]
+    Say,
+    /// A reply in the thread of the message that triggered the response
+    Reply,
+    /// A private message to the sender
+    Whisper,
+    /// A `/me` message
+    Action,
+    /// A highlighted message in the channel
+    Announcement,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Sender {
     pub name: String,
+    /// Twitch user id, that stays the same when the user is renamed
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub login: String,
     pub origin: MessageOrigin,
+    #[serde(default)]
+    pub roles: UserRoles,
+    /// Id of the message that has been sent, used to reply to it
+    #[serde(default)]
+    pub message_id: Option<String>,
+    /// Number of twitch emotes in the message
+    #[serde(default)]
+    pub emote_count: usize,
+}
+
+/// Reference to a twitch user, that survives renames.
+/// References created in older versions only know the name of the user,
+/// in which case the id is filled in once the user is seen again.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(from = "UserRefSerialized")]
+pub struct UserRef {
+    pub id: Option<String>,
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum UserRefSerialized {
+    Name(String),
+    Full { id: Option<String>, name: String },
+}
+
+/// Everything that defines what a user is allowed to do
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct UserRoles {
+    /// Authority levels granted by badges and custom roles
+    pub levels: Vec<AuthorityLevel>,
+    pub subscription: Option<Subscription>,
+    /// Roles granted by moderators
+    pub custom: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Subscription {
+    pub tier: u32,
+    pub months: u32,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
@@ -31,12 +95,30 @@ pub enum MessageOrigin {
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum AuthorityLevel {
     Viewer = 0,
-    Moderator = 1,
-    Broadcaster = 2,
-    Server = 3,
+    Subscriber = 1,
+    Founder = 2,
+    Artist = 3,
+    Vip = 4,
+    Moderator = 5,
+    Broadcaster = 6,
+    Server = 7,
 }
 
 impl AuthorityLevel {
+    /// Converts the level used in the command trees back, higher levels are treated as Server
+    pub fn from_level(level: usize) -> Self {
+        match level {
+            0 => Self::Viewer,
+            1 => Self::Subscriber,
+            2 => Self::Founder,
+            3 => Self::Artist,
+            4 => Self::Vip,
+            5 => Self::Moderator,
+            6 => Self::Broadcaster,
+            _ => Self::Server,
+        }
+    }
+
     pub fn from_badges(badges: &[twitch_irc::message::Badge]) -> Self {
         badges
             .iter()
@@ -49,16 +131,184 @@ impl AuthorityLevel {
         match badge.name.as_str() {
             "broadcaster" => AuthorityLevel::Broadcaster,
             "moderator" => AuthorityLevel::Moderator,
+            "vip" => AuthorityLevel::Vip,
+            "artist-badge" => AuthorityLevel::Artist,
+            "founder" => AuthorityLevel::Founder,
+            "subscriber" => AuthorityLevel::Subscriber,
             _ => AuthorityLevel::Viewer,
         }
     }
 }
 
+impl std::str::FromStr for AuthorityLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "viewer" => Ok(Self::Viewer),
+            "subscriber" => Ok(Self::Subscriber),
+            "founder" => Ok(Self::Founder),
+            "artist" => Ok(Self::Artist),
+            "vip" => Ok(Self::Vip),
+            "moderator" => Ok(Self::Moderator),
+            "broadcaster" => Ok(Self::Broadcaster),
+            "server" => Ok(Self::Server),
+            _ => Err(format!("Unknown authority level: {s}")),
+        }
+    }
+}
+
+impl UserRoles {
+    pub fn from_badges(
+        badges: &[twitch_irc::message::Badge],
+        badge_info: &[twitch_irc::message::Badge],
+    ) -> Self {
+        let levels = badges
+            .iter()
+            .map(AuthorityLevel::from_badge)
+            .filter(|&level| level != AuthorityLevel::Viewer)
+            .collect();
+
+        // Badge version encodes both the tier and the months, e.g. 3012 is tier 3 for 12 months,
+        // while badge info contains the exact number of months
+        let subscription = badges
+            .iter()
+            .find(|badge| badge.name == "subscriber" || badge.name == "founder")
+            .map(|badge| {
+                let version = badge.version.parse::<u32>().unwrap_or(0);
+                let months = badge_info
+                    .iter()
+                    .find(|info| info.name == badge.name)
+                    .and_then(|info| info.version.parse().ok())
+                    .unwrap_or(version % 1000);
+                Subscription {
+                    tier: (version / 1000).max(1),
+                    months,
+                }
+            });
+
+        Self {
+            levels,
+            subscription,
+            custom: Vec::new(),
+        }
+    }
+
+    /// Creates roles for a caller with a known authority level, e.g. the console
+    pub fn with_level(level: AuthorityLevel) -> Self {
+        Self {
+            levels: vec![level],
+            ..Default::default()
+        }
+    }
+
+    pub fn authority_level(&self) -> AuthorityLevel {
+        self.levels
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(AuthorityLevel::Viewer)
+    }
+
+    /// Checks whether the roles grant the level required by a command.
+    /// Subscriber, founder and artist come from separate badges, so they do not imply
+    /// each other and are not implied by VIP, only by moderators and above.
+    pub fn satisfies(&self, required: AuthorityLevel) -> bool {
+        let level = self.authority_level();
+        match required {
+            AuthorityLevel::Subscriber => {
+                self.subscription.is_some()
+                    || self.levels.iter().any(|level| {
+                        matches!(level, AuthorityLevel::Subscriber | AuthorityLevel::Founder)
+                    })
+                    || level >= AuthorityLevel::Moderator
+            }
+            AuthorityLevel::Founder | AuthorityLevel::Artist => {
+                self.levels.contains(&required) || level >= AuthorityLevel::Moderator
+            }
+            _ => level >= required,
+        }
+    }
+}
+
+impl Sender {
+    pub fn user(&self) -> UserRef {
+        UserRef {
+            id: self.id.clone(),
+            name: self.name.clone(),
+        }
+    }
+}
+
+impl UserRef {
+    /// Creates a reference to the user, whose id is unknown
+    pub fn from_name(name: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            name: name.into(),
+        }
+    }
+
+    /// Parses the user mentioned in a command, e.g. `@Name` or `name`
+    pub fn parse(input: &str) -> Self {
+        Self::from_name(input.trim().trim_start_matches('@'))
+    }
+
+    /// Looks for the user among the known ones, ignoring the case of the names
+    pub fn resolve<'a>(&self, known: impl IntoIterator<Item = &'a UserRef>) -> Option<&'a UserRef> {
+        known.into_iter().find(|user| user.matches(self))
+    }
+
+    /// Compares the ids if both are known, otherwise falls back to the names
+    pub fn matches(&self, other: &UserRef) -> bool {
+        match (&self.id, &other.id) {
+            (Some(id), Some(other_id)) => id == other_id,
+            _ => same_name(&self.name, &other.name),
+        }
+    }
+
+    /// Fills in the id and the current name, if the reference points to the user
+    pub fn update(&mut self, user: &UserRef) -> bool {
+        if user.id.is_none() || !self.matches(user) {
+            return false;
+        }
+        let changed = self.id != user.id || self.name != user.name;
+        self.id = user.id.clone();
+        self.name = user.name.clone();
+        changed
+    }
+}
+
+/// Twitch names are case-insensitive
+pub fn same_name(name: &str, other: &str) -> bool {
+    name.to_lowercase() == other.to_lowercase()
+}
+
+impl From<UserRefSerialized> for UserRef {
+    fn from(user: UserRefSerialized) -> Self {
+        match user {
+            UserRefSerialized::Name(name) => Self::from_name(name),
+            UserRefSerialized::Full { id, name } => Self { id, name },
+        }
+    }
+}
+
+impl std::fmt::Display for UserRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
 pub fn private_to_command_message(message: &PrivmsgMessage) -> CommandMessage {
     CommandMessage {
         sender: Sender {
             name: message.sender.name.clone(),
+            id: Some(message.sender.id.clone()),
+            login: message.sender.login.clone(),
             origin: MessageOrigin::Twitch,
+            roles: UserRoles::from_badges(&message.badges, &message.badge_info),
+            message_id: Some(message.message_id.clone()),
+            emote_count: message.emotes.len(),
         },
         message_text: message.message_text.clone(),
         authority_level: AuthorityLevel::from_badges(&message.badges) as usize,
@@ -70,8 +320,13 @@ impl ResponseMsg {
         Self {
             message: message.to_string(),
             send_to_twitch: false,
+            delivery: Delivery::Say,
         }
     }
+
+    pub fn with_delivery(self, delivery: Delivery) -> Self {
+        Self { delivery, ..self }
+    }
 }
 
 impl<T: ToString> From<T> for ResponseMsg {
@@ -0,0 +1,204 @@
+use super::*;
+
+impl AnnounceBot {
+    fn announce_add(
+        &mut self,
+        name: &str,
+        interval: std::time::Duration,
+        message: &str,
+    ) -> Response {
+        if self.find(name).is_some() {
+            return Some(format!("An announcement named {name} already exists").into());
+        }
+        if let Err(error) = check_interval(interval) {
+            return Some(error.into());
+        }
+        self.config.announcements.push(Announcement {
+            name: name.to_owned(),
+            message: message.to_owned(),
+            interval: interval.as_secs_f32(),
+            min_lines: 0,
+            condition: None,
+            enabled: true,
+        });
+        self.config.save().unwrap();
+        Some(format!("Added the announcement {name}").into())
+    }
+
+    fn announce_remove(&mut self, name: &str) -> Response {
+        let index = match self.find(name) {
+            Some(index) => index,
+            None => return Some(format!("There is no announcement named {name}").into()),
+        };
+        let announcement = self.config.announcements.remove(index);
+        self.timers.remove(&announcement.name);
+        self.config.save().unwrap();
+        Some(format!("Removed the announcement {name}: {}", announcement.message).into())
+    }
+
+    fn announce_set(&mut self, name: &str, field: &str, value: &str) -> Response {
+        let index = match self.find(name) {
+            Some(index) => index,
+            None => return Some(format!("There is no announcement named {name}").into()),
+        };
+        let announcement = &mut self.config.announcements[index];
+        match field {
+            "message" => announcement.message = value.to_owned(),
+            "interval" => match parse_duration(value).and_then(check_interval) {
+                Ok(interval) => announcement.interval = interval.as_secs_f32(),
+                Err(error) => return Some(error.into()),
+            },
+            "lines" => match value.parse() {
+                Ok(lines) => announcement.min_lines = lines,
+                Err(_) => return Some(format!("{value} is not a number").into()),
+            },
+            "condition" => {
+                announcement.condition = match value.split_once('=') {
+                    Some((variable, value)) => Some(Condition {
+                        variable: variable.trim().to_owned(),
+                        value: value.trim().to_owned(),
+                    }),
+                    None if value == "none" => None,
+                    None => {
+                        return Some("Expected <variable>=<value>, e.g. queue_open=true".into())
+                    }
+                }
+            }
+            "enabled" => match value {
+                "true" | "on" | "yes" => announcement.enabled = true,
+                "false" | "off" | "no" => announcement.enabled = false,
+                _ => return Some(format!("Expected true or false, got {value}").into()),
+            },
+            _ => return Some(format!("Unknown field {field}").into()),
+        }
+        self.config.save().unwrap();
+        Some(format!("Updated {field} of the announcement {name}").into())
+    }
+
+    fn announce_rotation(&mut self, rotation: &str) -> Response {
+        self.config.rotation = match rotation {
+            "random" => Rotation::Random,
+            _ => Rotation::InOrder,
+        };
+        self.config.save().unwrap();
+        Some(
+            format!(
+                "Announcements are now posted {}",
+                rotation.replace('_', " ")
+            )
+            .into(),
+        )
+    }
+
+    fn announce_list(&self) -> Response {
+        if self.config.announcements.is_empty() {
+            return Some("There are no announcements".into());
+        }
+        let announcements = self
+            .config
+            .announcements
+            .iter()
+            .map(|announcement| {
+                let mut line = format!(
+                    "{} (every {}",
+                    announcement.name,
+                    format_interval(announcement.interval as u64)
+                );
+                if announcement.min_lines > 0 {
+                    line += &format!(", after {} messages", announcement.min_lines);
+                }
+                if let Some(condition) = &announcement.condition {
+                    line += &format!(", when {}={}", condition.variable, condition.value);
+                }
+                if !announcement.enabled {
+                    line += ", disabled";
+                }
+                line + ")"
+            })
+            .collect::<Vec<_>>();
+        Some(format!("Announcements: {}", announcements.join(", ")).into())
+    }
+
+    fn find(&self, name: &str) -> Option<usize> {
+        self.config
+            .announcements
+            .iter()
+            .position(|announcement| announcement.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn commands() -> Commands<Self> {
+        let add = TypedCommand::<Self>::new("!announce add")
+            .word("name")
+            .duration("interval")
+            .line("message")
+            .finalize(AuthorityLevel::Moderator as _, |bot, _, args| {
+                bot.announce_add(args.text(0)?, args.duration(1)?, args.text(2)?)
+            });
+
+        let remove = TypedCommand::<Self>::new("!announce remove")
+            .word("name")
+            .finalize(AuthorityLevel::Moderator as _, |bot, _, args| {
+                bot.announce_remove(args.text(0)?)
+            });
+
+        let set = TypedCommand::<Self>::new("!announce set")
+            .word("name")
+            .choice(
+                "field",
+                ["message", "interval", "lines", "condition", "enabled"],
+            )
+            .line("value")
+            .finalize(AuthorityLevel::Moderator as _, |bot, _, args| {
+                bot.announce_set(args.text(0)?, args.text(1)?, args.text(2)?)
+            });
+
+        let rotation = TypedCommand::<Self>::new("!announce rotation")
+            .choice("rotation", ["in_order", "random"])
+            .finalize(AuthorityLevel::Moderator as _, |bot, _, args| {
+                bot.announce_rotation(args.text(0)?)
+            });
+
+        let list = CommandBuilder::<Self>::new().literal(["list"]).finalize(
+            true,
+            AuthorityLevel::Moderator as _,
+            Arc::new(|bot, _, _| bot.announce_list()),
+        );
+
+        Commands::new(vec![
+            add,
+            remove,
+            set,
+            rotation,
+            CommandBuilder::new().literal(["!announce"]).split([list]),
+        ])
+    }
+}
+
+fn check_interval(interval: std::time::Duration) -> Result<std::time::Duration, String> {
+    if interval.as_secs_f32() < MIN_INTERVAL {
+        return Err(format!(
+            "The interval should be at least {}",
+            format_interval(MIN_INTERVAL as u64)
+        ));
+    }
+    Ok(interval)
+}
+
+/// Formats the interval like `1h 30m` or `45s`
+fn format_interval(seconds: u64) -> String {
+    let units = [
+        (seconds / 3600, "h"),
+        (seconds / 60 % 60, "m"),
+        (seconds % 60, "s"),
+    ];
+    let parts = units
+        .iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{value}{unit}"))
+        .collect::<Vec<_>>();
+    if parts.is_empty() {
+        "0s".to_owned()
+    } else {
+        parts.join(" ")
+    }
+}
@@ -0,0 +1,221 @@
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+
+use super::*;
+
+mod commands;
+
+const CONFIG_FILE: &str = "config/announce/announce_config.json";
+/// Minimum seconds between two posts of an announcement, so that it can not flood the chat
+const MIN_INTERVAL: f32 = 60.0;
+
+pub struct AnnounceBot {
+    cli: Option<Cli>,
+    shared: Shared,
+    config: AnnounceConfig,
+    commands: Commands<Self>,
+    /// Progress of every announcement by its name
+    timers: HashMap<String, AnnounceTimer>,
+    /// Position in the list to look for the next announcement from
+    next_index: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnnounceSerialized {
+    config: AnnounceConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct AnnounceConfig {
+    announcements: Vec<Announcement>,
+    #[serde(default)]
+    rotation: Rotation,
+}
+
+/// Message posted on a schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Announcement {
+    name: String,
+    message: String,
+    /// Seconds between two posts
+    interval: f32,
+    /// Chat messages that have to be sent since the last post
+    #[serde(default)]
+    min_lines: u64,
+    #[serde(default)]
+    condition: Option<Condition>,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+}
+
+/// Posts the announcement only when a variable has the value,
+/// e.g. `queue_open` provided by GamejamBot is `true`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Condition {
+    variable: String,
+    value: String,
+}
+
+/// Order, in which the announcements are posted, when several are ready
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+enum Rotation {
+    /// In the order of the list
+    #[default]
+    InOrder,
+    Random,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct AnnounceTimer {
+    /// Seconds since the last post
+    time: f32,
+    /// Chat messages since the last post
+    lines: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl AnnounceBot {
+    pub fn new_boxed(cli: &Option<Cli>, shared: &Shared) -> Box<dyn Bot> {
+        let config = match AnnounceConfig::load() {
+            Ok(config) => config,
+            Err(error) => match error.kind() {
+                std::io::ErrorKind::NotFound => {
+                    let config = AnnounceConfig::default();
+                    config.save().unwrap();
+                    config
+                }
+                _ => panic!("{}", error),
+            },
+        };
+        Box::new(Self {
+            cli: cli.clone(),
+            shared: shared.clone(),
+            config,
+            commands: Self::commands(),
+            timers: HashMap::new(),
+            next_index: 0,
+        })
+    }
+
+    /// Picks the next announcement, that is ready to be posted
+    fn update(&mut self, delta_time: f32) -> Option<String> {
+        for announcement in &self.config.announcements {
+            self.timers
+                .entry(announcement.name.clone())
+                .or_default()
+                .time += delta_time;
+        }
+
+        let ready = {
+            let shared = self.shared.lock().unwrap();
+            self.config
+                .announcements
+                .iter()
+                .enumerate()
+                .filter(|(_, announcement)| {
+                    let timer = self.timers[&announcement.name];
+                    announcement.enabled
+                        && timer.time >= announcement.interval.max(MIN_INTERVAL)
+                        && timer.lines >= announcement.min_lines
+                        && announcement.condition.as_ref().is_none_or(|condition| {
+                            shared.variables.get(&condition.variable)
+                                == Some(condition.value.as_str())
+                        })
+                })
+                .map(|(index, _)| index)
+                .collect::<Vec<_>>()
+        };
+        let index = match self.config.rotation {
+            Rotation::InOrder => ready
+                .iter()
+                .find(|index| **index >= self.next_index)
+                .or_else(|| ready.first())
+                .copied(),
+            Rotation::Random => ready.choose(&mut rand::thread_rng()).copied(),
+        }?;
+
+        let announcement = &self.config.announcements[index];
+        self.timers
+            .insert(announcement.name.clone(), AnnounceTimer::default());
+        self.next_index = index + 1;
+        Some(announcement.message.clone())
+    }
+}
+
+impl AnnounceConfig {
+    fn save(&self) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(
+            std::io::BufWriter::new(std::fs::File::create(CONFIG_FILE)?),
+            self,
+        )?;
+        Ok(())
+    }
+
+    fn load() -> std::io::Result<Self> {
+        Ok(serde_json::from_reader(std::io::BufReader::new(
+            std::fs::File::open(CONFIG_FILE)?,
+        ))?)
+    }
+}
+
+impl BotPerformer for AnnounceBot {
+    const NAME: &'static str = "AnnounceBot";
+
+    fn commands(&self) -> &Commands<Self> {
+        &self.commands
+    }
+}
+
+#[async_trait]
+impl Bot for AnnounceBot {
+    async fn handle_message(
+        &mut self,
+        shared: &Shared,
+        outbox: &Outbox,
+        channel: &ChannelLogin,
+        message: &CommandMessage,
+    ) {
+        if let MessageOrigin::Twitch = message.sender.origin {
+            for timer in self.timers.values_mut() {
+                timer.lines += 1;
+            }
+        }
+        self.perform(&self.cli.clone(), shared, outbox, channel, message)
+            .await;
+    }
+
+    async fn update(
+        &mut self,
+        _shared: &Shared,
+        outbox: &Outbox,
+        channel: &ChannelLogin,
+        delta_time: f32,
+    ) {
+        if let Some(message) = self.update(delta_time) {
+            outbox.send(channel.clone(), message);
+        }
+    }
+
+    fn complete(
+        &self,
+        word: &str,
+        prompter: &Prompter,
+        start: usize,
+        end: usize,
+    ) -> Option<Vec<linefeed::Completion>> {
+        self.commands.complete(word, prompter, start, end)
+    }
+
+    fn help(&self) -> Vec<CommandInfo> {
+        command_infos(&self.commands)
+    }
+
+    fn serialize(&self) -> SerializedBot {
+        SerializedBot::Announce(AnnounceSerialized {
+            config: self.config.clone(),
+        })
+    }
+}
@@ -0,0 +1,152 @@
+use super::*;
+
+impl CounterBot {
+    fn counter_show(&self, counter_name: &str) -> Response {
+        let counter = self.find(counter_name)?;
+        Some(format!("{}: {}", counter.name, counter.value).into())
+    }
+
+    fn counter_change(
+        &mut self,
+        counter_name: &str,
+        action: &str,
+        value: Option<&str>,
+    ) -> Response {
+        let counter_name = counter_name.to_lowercase();
+        let counter_name = counter_name.trim_start_matches('!');
+        if counter_name.is_empty()
+            || !counter_name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Some("Counter names can only contain letters, digits and _".into());
+        }
+        let number = match (action, value) {
+            ("autoreset", _) | (_, None) => None,
+            (_, Some(value)) => match value.parse::<i64>() {
+                Ok(number) => Some(number),
+                Err(_) => return Some(format!("{value} is not a number").into()),
+            },
+        };
+
+        let exists = self.find(counter_name).is_some();
+        if !exists {
+            if !matches!(action, "+" | "-" | "set") {
+                return Some(format!("There is no counter named {counter_name}").into());
+            }
+            let command_name = format!("!{counter_name}");
+            if self
+                .shared
+                .lock()
+                .unwrap()
+                .command_names
+                .contains(&command_name)
+            {
+                return Some(format!("{command_name} is already a command").into());
+            }
+            self.state.counters.push(Counter {
+                name: counter_name.to_owned(),
+                value: 0,
+                reset_on_next_game: false,
+            });
+            self.push_counter(counter_name.to_owned());
+        }
+
+        let counter = self
+            .state
+            .counters
+            .iter_mut()
+            .find(|counter| counter.name == counter_name)?;
+        let reply = match action {
+            "+" => {
+                counter.value = counter.value.saturating_add(number.unwrap_or(1));
+                format!("{}: {}", counter.name, counter.value)
+            }
+            "-" => {
+                counter.value = counter.value.saturating_sub(number.unwrap_or(1));
+                format!("{}: {}", counter.name, counter.value)
+            }
+            "set" => match number {
+                Some(number) => {
+                    counter.value = number;
+                    format!("{}: {}", counter.name, counter.value)
+                }
+                None => return Some(format!("Usage: !counter {counter_name} set <value>").into()),
+            },
+            "reset" => {
+                counter.value = 0;
+                format!("{} has been reset", counter.name)
+            }
+            "autoreset" => {
+                counter.reset_on_next_game = match value {
+                    Some("on" | "true" | "yes") => true,
+                    Some("off" | "false" | "no") => false,
+                    _ => {
+                        return Some(
+                            format!("Usage: !counter {counter_name} autoreset <on|off>").into(),
+                        )
+                    }
+                };
+                if counter.reset_on_next_game {
+                    format!("{} will be reset on the next game", counter.name)
+                } else {
+                    format!("{} will not be reset on the next game", counter.name)
+                }
+            }
+            "delete" => {
+                self.state
+                    .counters
+                    .retain(|counter| counter.name != counter_name);
+                self.remove_counter(counter_name);
+                let path = format!("status/{}_{}.txt", Self::NAME, counter_name);
+                std::fs::remove_file(path).unwrap_or(());
+                format!("Deleted the counter {counter_name}")
+            }
+            _ => return None,
+        };
+        self.save_counters();
+        Some(reply.into())
+    }
+
+    fn find(&self, counter_name: &str) -> Option<&Counter> {
+        let counter_name = counter_name.trim_start_matches('!');
+        self.state
+            .counters
+            .iter()
+            .find(|counter| counter.name.eq_ignore_ascii_case(counter_name))
+    }
+
+    fn remove_counter(&mut self, counter_name: &str) {
+        let literal = format!("!{counter_name}");
+        self.commands.commands.retain(|command| match command {
+            CommandNode::Literal { literals, .. } => !literals.contains(&literal),
+            _ => true,
+        });
+        self.shared.lock().unwrap().command_names_outdated = true;
+    }
+
+    /// Adds `!<name>` showing the value of the counter
+    pub fn push_counter(&mut self, counter_name: String) {
+        self.commands.commands.push(
+            CommandBuilder::new()
+                .literal([format!("!{counter_name}")])
+                .finalize(
+                    true,
+                    AuthorityLevel::Viewer as _,
+                    Arc::new(move |bot, _, _| bot.counter_show(&counter_name)),
+                ),
+        );
+        self.shared.lock().unwrap().command_names_outdated = true;
+    }
+
+    pub fn commands() -> Commands<Self> {
+        Commands::new(vec![TypedCommand::<Self>::new("!counter")
+            .word("name")
+            .choice("action", ["+", "-", "set", "reset", "autoreset", "delete"])
+            .word("value")
+            .optional()
+            .finalize(AuthorityLevel::Moderator as _, |bot, _, args| {
+                bot.counter_change(args.text(0)?, args.text(1)?, args.text(2))
+            })])
+    }
+}
@@ -0,0 +1,184 @@
+use super::*;
+
+mod commands;
+
+const COUNTERS_FILE: &str = "config/counter/counters.json";
+
+pub struct CounterBot {
+    cli: Option<Cli>,
+    shared: Shared,
+    commands: Commands<Self>,
+    state: CounterState,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CounterState {
+    counters: Vec<Counter>,
+    /// Last game reported by GamejamBot, used to reset the counters on the next game
+    #[serde(default)]
+    last_game: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Counter {
+    name: String,
+    value: i64,
+    /// Whether the counter is reset when GamejamBot moves to the next game
+    #[serde(default)]
+    reset_on_next_game: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CounterSerialized {
+    state: CounterState,
+}
+
+impl CounterBot {
+    pub fn new_boxed(cli: &Option<Cli>, shared: &Shared) -> Box<dyn Bot> {
+        let state = match CounterState::load() {
+            Ok(state) => state,
+            Err(error) => match error.kind() {
+                std::io::ErrorKind::NotFound => {
+                    let state = CounterState::default();
+                    state.save().unwrap();
+                    state
+                }
+                _ => panic!("{}", error),
+            },
+        };
+        let mut bot = Self {
+            cli: cli.clone(),
+            shared: shared.clone(),
+            commands: Self::commands(),
+            state,
+        };
+        let counter_names = bot
+            .state
+            .counters
+            .iter()
+            .map(|counter| counter.name.clone())
+            .collect::<Vec<_>>();
+        for counter_name in counter_names {
+            bot.push_counter(counter_name);
+        }
+        bot.save_counters();
+        Box::new(bot)
+    }
+
+    /// Saves the counters and writes them into the status files,
+    /// `status/CounterBot.txt` with every counter and `status/CounterBot_<name>.txt` with a single value
+    fn save_counters(&self) {
+        self.state.save().unwrap();
+        let status = self
+            .state
+            .counters
+            .iter()
+            .map(|counter| format!("{}: {}", counter.name, counter.value))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.update_status(&status);
+        for counter in &self.state.counters {
+            let path = format!("status/{}_{}.txt", Self::NAME, counter.name);
+            std::fs::write(path, counter.value.to_string()).expect("Could not update bot status");
+        }
+    }
+
+    /// Resets the counters when GamejamBot starts playing the next game.
+    /// The first game seen is only remembered, so that a restart does not reset the counters.
+    fn check_next_game(&mut self, current_game: Option<&str>) {
+        let current_game = match current_game.filter(|game| *game != "nothing") {
+            Some(game) => game,
+            None => return,
+        };
+        if self.state.last_game.as_deref() == Some(current_game) {
+            return;
+        }
+        let is_first = self
+            .state
+            .last_game
+            .replace(current_game.to_owned())
+            .is_none();
+        if !is_first {
+            for counter in &mut self.state.counters {
+                if counter.reset_on_next_game {
+                    counter.value = 0;
+                }
+            }
+        }
+        self.save_counters();
+    }
+}
+
+impl CounterState {
+    fn save(&self) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(
+            std::io::BufWriter::new(std::fs::File::create(COUNTERS_FILE)?),
+            self,
+        )?;
+        Ok(())
+    }
+
+    fn load() -> std::io::Result<Self> {
+        Ok(serde_json::from_reader(std::io::BufReader::new(
+            std::fs::File::open(COUNTERS_FILE)?,
+        ))?)
+    }
+}
+
+impl BotPerformer for CounterBot {
+    const NAME: &'static str = "CounterBot";
+
+    fn commands(&self) -> &Commands<Self> {
+        &self.commands
+    }
+}
+
+#[async_trait]
+impl Bot for CounterBot {
+    async fn handle_message(
+        &mut self,
+        shared: &Shared,
+        outbox: &Outbox,
+        channel: &ChannelLogin,
+        message: &CommandMessage,
+    ) {
+        self.perform(&self.cli.clone(), shared, outbox, channel, message)
+            .await;
+    }
+
+    async fn update(
+        &mut self,
+        shared: &Shared,
+        _outbox: &Outbox,
+        _channel: &ChannelLogin,
+        _delta_time: f32,
+    ) {
+        let current_game = shared
+            .lock()
+            .unwrap()
+            .variables
+            .get("current_game")
+            .map(|game| game.to_owned());
+        self.check_next_game(current_game.as_deref());
+    }
+
+    fn complete(
+        &self,
+        word: &str,
+        prompter: &Prompter,
+        start: usize,
+        end: usize,
+    ) -> Option<Vec<linefeed::Completion>> {
+        self.commands.complete(word, prompter, start, end)
+    }
+
+    fn help(&self) -> Vec<CommandInfo> {
+        command_infos(&self.commands)
+    }
+
+    fn serialize(&self) -> SerializedBot {
+        SerializedBot::Counter(CounterSerialized {
+            state: self.state.clone(),
+        })
+    }
+}
@@ -1,18 +1,32 @@
+use rand::seq::SliceRandom;
+
 use super::*;
 
 impl CustomBot {
-    fn command_new(&mut self, command_name: String, command_response: String) -> Response {
-        if self.config.commands.contains_key(&command_name) {
+    fn command_new(
+        &mut self,
+        sender: &Sender,
+        command_name: String,
+        command_response: String,
+    ) -> Response {
+        if let Err(error) = self.check_template(&command_response) {
+            return Some(format!("Invalid response: {error}").into());
+        }
+        if self.is_taken(&command_name, None) {
             Some(
                 format!(
-                "A command with that name already exists. Try !edit {command_name} <new response>"
+                "A command with that name already exists. Try !command edit {command_name} <new response>"
             )
                 .into(),
             )
         } else {
             let response =
                 Some(format!("Added new command: {command_name}: {command_response}").into());
-            self.command_edit(command_name, command_response);
+            self.config.commands.insert(
+                command_name.clone(),
+                CustomCommand::new(command_response, Some(sender.name.clone())),
+            );
+            self.push_command(command_name);
             self.config.save().unwrap();
             response
         }
@@ -20,45 +34,246 @@ impl CustomBot {
 
     fn command_delete(&mut self, command_name: &str) -> Response {
         match self.config.commands.remove(command_name) {
-            Some(command_response) => {
+            Some(command) => {
                 self.remove_command(command_name);
                 self.config.save().unwrap();
-                Some(format!("Removed the command: {command_name}: {command_response}").into())
+                Some(
+                    format!(
+                        "Removed the command: {command_name}: {}",
+                        command.responses.join(" | ")
+                    )
+                    .into(),
+                )
             }
             None => Some("A command with that name does not exist".into()),
         }
     }
 
     fn command_edit(&mut self, command_name: String, command_response: String) -> Response {
+        if let Err(error) = self.check_template(&command_response) {
+            return Some(format!("Invalid response: {error}").into());
+        }
+        let command = match self.config.commands.get_mut(&command_name) {
+            Some(command) => command,
+            None => return Some("A command with that name does not exist".into()),
+        };
         let response =
             Some(format!("Updated command to {command_name}: {command_response}").into());
-        self.config
-            .commands
-            .insert(command_name.clone(), command_response);
-        self.remove_command(&command_name);
-        self.push_command(command_name);
+        command.responses = vec![command_response];
+        command.edited = unix_time();
         self.config.save().unwrap();
         response
     }
 
+    fn command_set(&mut self, command_name: &str, field: &str, value: &str) -> Response {
+        if !self.config.commands.contains_key(command_name) {
+            return Some("A command with that name does not exist".into());
+        }
+        let value = value.trim();
+        match field {
+            "responses" => {
+                let responses = value
+                    .split(" | ")
+                    .map(|response| response.trim().to_owned())
+                    .filter(|response| !response.is_empty())
+                    .collect::<Vec<_>>();
+                for response in &responses {
+                    if let Err(error) = self.check_template(response) {
+                        return Some(format!("Invalid response: {error}").into());
+                    }
+                }
+                self.config.commands.get_mut(command_name)?.responses = responses;
+            }
+            "authority" => {
+                let authority = match value.parse::<AuthorityLevel>() {
+                    Ok(authority) => authority,
+                    Err(error) => return Some(error.into()),
+                };
+                self.config.commands.get_mut(command_name)?.authority = authority;
+            }
+            "cooldown" => {
+                let cooldown = match parse_cooldown(value) {
+                    Ok(cooldown) => cooldown,
+                    Err(error) => return Some(error.into()),
+                };
+                self.config.commands.get_mut(command_name)?.cooldown = cooldown;
+            }
+            "aliases" => {
+                let aliases = value
+                    .split_whitespace()
+                    .filter(|alias| *alias != "none")
+                    .map(|alias| alias.to_owned())
+                    .collect::<Vec<_>>();
+                if let Some(alias) = aliases
+                    .iter()
+                    .find(|alias| self.is_taken(alias, Some(command_name)))
+                {
+                    return Some(format!("{alias} is already a command").into());
+                }
+                self.config.commands.get_mut(command_name)?.aliases = aliases;
+            }
+            "enabled" => {
+                let enabled = match value {
+                    "true" | "on" | "yes" => true,
+                    "false" | "off" | "no" => false,
+                    _ => return Some(format!("Expected true or false, got {value}").into()),
+                };
+                self.config.commands.get_mut(command_name)?.enabled = enabled;
+            }
+            _ => return Some(format!("Unknown field {field}").into()),
+        }
+        self.config.commands.get_mut(command_name)?.edited = unix_time();
+        // Authority and aliases are a part of the command tree
+        self.remove_command(command_name);
+        self.push_command(command_name.to_owned());
+        self.config.save().unwrap();
+        Some(format!("Updated {field} of {command_name}").into())
+    }
+
+    fn command_list(&self) -> Response {
+        if self.config.commands.is_empty() {
+            return Some("There are no custom commands".into());
+        }
+        let mut commands = self
+            .config
+            .commands
+            .iter()
+            .map(|(command_name, command)| {
+                let mut line = command_name.clone();
+                if !command.aliases.is_empty() {
+                    line += &format!(" ({})", command.aliases.join(", "));
+                }
+                if !command.enabled {
+                    line += " [disabled]";
+                }
+                line
+            })
+            .collect::<Vec<_>>();
+        commands.sort();
+        Some(format!("Custom commands: {}", commands.join(", ")).into())
+    }
+
+    /// Checks whether the name is used by a custom command or its aliases,
+    /// except for the given command, or by a command of the other bots
+    fn is_taken(&self, name: &str, except: Option<&str>) -> bool {
+        let custom = self.config.commands.iter().find(|(command_name, command)| {
+            *command_name == name || command.aliases.iter().any(|alias| alias == name)
+        });
+        match custom {
+            Some((command_name, _)) => Some(command_name.as_str()) != except,
+            // Custom commands are among the command names too, so only the other bots are left
+            None => self.shared.lock().unwrap().command_names.contains(name),
+        }
+    }
+
+    pub(super) fn check_template(&self, command_response: &str) -> Result<(), String> {
+        let template = Template::parse(command_response)?;
+        template.validate(&self.shared.lock().unwrap().variables)
+    }
+
+    /// Fills in a random response of the command and counts its usage
+    fn command_response(&mut self, command_name: &str, sender: &Sender, args: &str) -> Response {
+        let command = self.config.commands.get_mut(command_name)?;
+        if !command.enabled {
+            return None;
+        }
+        command.uses += 1;
+        let count = command.uses;
+        let command_response = command.responses.choose(&mut rand::thread_rng())?.clone();
+        self.count_use();
+
+        // Responses made before the templates may not parse, those are sent as they are
+        let template = match Template::parse(&command_response) {
+            Ok(template) => template,
+            Err(_) => return Some(command_response.into()),
+        };
+        let shared = self.shared.lock().unwrap();
+        let context = TemplateContext {
+            sender,
+            args,
+            count,
+            shared: &shared,
+        };
+        Some(template.render(&context).into())
+    }
+
+    fn alias_add(&mut self, alias: &str, command: &str) -> Response {
+        if self.is_taken(alias, None) {
+            return Some(format!("{alias} is already a command").into());
+        }
+        let result = self
+            .shared
+            .lock()
+            .unwrap()
+            .aliases
+            .add(&self.channel, alias, command);
+        match result {
+            Ok(()) => Some(format!("{alias} now calls {command}").into()),
+            Err(err) => {
+                log(
+                    &self.cli,
+                    LogType::Error,
+                    &format!("Failed to save aliases: {err}"),
+                );
+                Some("Failed to add the alias".into())
+            }
+        }
+    }
+
+    fn alias_remove(&mut self, alias: &str) -> Response {
+        let result = self
+            .shared
+            .lock()
+            .unwrap()
+            .aliases
+            .remove(&self.channel, alias);
+        match result {
+            Ok(Some(command)) => Some(format!("Removed the alias: {alias}: {command}").into()),
+            Ok(None) => Some("An alias with that name does not exist".into()),
+            Err(err) => {
+                log(
+                    &self.cli,
+                    LogType::Error,
+                    &format!("Failed to save aliases: {err}"),
+                );
+                Some("Failed to remove the alias".into())
+            }
+        }
+    }
+
     fn remove_command(&mut self, command_name: &str) {
         self.commands.commands.retain(|command| match command {
             CommandNode::Literal { literals, .. } => {
                 !literals.iter().any(|literal| *literal == *command_name)
             }
             _ => true,
-        })
+        });
+        self.shared.lock().unwrap().command_names_outdated = true;
     }
 
     pub fn push_command(&mut self, command_name: String) {
+        let custom = match self.config.commands.get(&command_name) {
+            Some(custom) => custom,
+            None => return,
+        };
+        let literals = std::iter::once(command_name.clone())
+            .chain(custom.aliases.iter().cloned())
+            .collect();
+        let authority_level = custom.authority as usize;
+        let command = Arc::new(move |bot: &mut Self, sender: &Sender, args: Vec<String>| {
+            let args = args.first().map(|args| args.as_str()).unwrap_or("");
+            bot.command_response(&command_name, sender, args)
+        });
         self.commands.commands.push(CommandNode::Literal {
-            literals: vec![command_name.clone()],
-            child_nodes: vec![CommandNode::final_node(
-                true,
-                AuthorityLevel::Viewer as usize,
-                Arc::new(move |bot, _, _| Some((&bot.config.commands[&command_name]).into())),
-            )],
+            literals,
+            child_nodes: vec![
+                CommandNode::final_node(true, authority_level, command.clone()),
+                CommandBuilder::new()
+                    .line()
+                    .finalize(true, authority_level, command),
+            ],
         });
+        self.shared.lock().unwrap().command_names_outdated = true;
     }
 
     pub fn commands() -> Commands<Self> {
@@ -69,7 +284,9 @@ impl CustomBot {
             .finalize(
                 true,
                 AuthorityLevel::Moderator as _,
-                Arc::new(|bot, _, args| bot.command_new(args[0].to_owned(), args[1].to_owned())),
+                Arc::new(|bot, sender, args| {
+                    bot.command_new(sender, args[0].to_owned(), args[1].to_owned())
+                }),
             );
 
         let delete = CommandBuilder::<Self>::new()
@@ -91,10 +308,89 @@ impl CustomBot {
                 Arc::new(|bot, _, args| bot.command_edit(args[0].to_owned(), args[1].to_owned())),
             );
 
+        let alias = CommandBuilder::<Self>::new()
+            .literal(["alias"])
+            .word()
+            .line()
+            .finalize(
+                true,
+                AuthorityLevel::Moderator as _,
+                Arc::new(|bot, _, args| bot.alias_add(&args[0], &args[1])),
+            );
+
+        let unalias = CommandBuilder::<Self>::new()
+            .literal(["unalias"])
+            .word()
+            .finalize(
+                true,
+                AuthorityLevel::Moderator as _,
+                Arc::new(|bot, _, args| bot.alias_remove(&args[0])),
+            );
+
+        let list = CommandBuilder::<Self>::new().literal(["list"]).finalize(
+            true,
+            AuthorityLevel::Viewer as _,
+            Arc::new(|bot, _, _| bot.command_list()),
+        );
+
+        let set = TypedCommand::<Self>::new("!command set")
+            .word("command_name")
+            .choice(
+                "field",
+                ["responses", "authority", "cooldown", "aliases", "enabled"],
+            )
+            .line("value")
+            .finalize(AuthorityLevel::Moderator as _, |bot, _, args| {
+                bot.command_set(args.text(0)?, args.text(1)?, args.text(2)?)
+            });
+
+        let trigger_add = TypedCommand::<Self>::new("!trigger add")
+            .word("name")
+            .line("pattern => response")
+            .finalize(AuthorityLevel::Moderator as _, |bot, _, args| {
+                bot.trigger_add(args.text(0)?, args.text(1)?)
+            });
+
+        let trigger_delete = TypedCommand::<Self>::new("!trigger delete")
+            .word("name")
+            .finalize(AuthorityLevel::Moderator as _, |bot, _, args| {
+                bot.trigger_delete(args.text(0)?)
+            });
+
+        let trigger_set = TypedCommand::<Self>::new("!trigger set")
+            .word("name")
+            .choice("field", ["pattern", "response", "cooldown", "authority"])
+            .line("value")
+            .finalize(AuthorityLevel::Moderator as _, |bot, _, args| {
+                bot.trigger_set(args.text(0)?, args.text(1)?, args.text(2)?)
+            });
+
+        let trigger_test = TypedCommand::<Self>::new("!trigger test")
+            .line("text")
+            .finalize(AuthorityLevel::Server as _, |bot, _, args| {
+                bot.trigger_test(args.text(0)?)
+            });
+
+        let trigger_list = CommandBuilder::<Self>::new().literal(["list"]).finalize(
+            true,
+            AuthorityLevel::Moderator as _,
+            Arc::new(|bot, _, _| bot.trigger_list()),
+        );
+
         Commands {
-            commands: vec![CommandBuilder::new()
-                .literal(["!command"])
-                .split(vec![new, delete, edit])],
+            commands: vec![
+                trigger_add,
+                trigger_delete,
+                trigger_set,
+                trigger_test,
+                CommandBuilder::new()
+                    .literal(["!trigger"])
+                    .split([trigger_list]),
+                set,
+                CommandBuilder::new()
+                    .literal(["!command"])
+                    .split(vec![new, delete, edit, alias, unalias, list]),
+            ],
         }
     }
 }
@@ -2,12 +2,27 @@ use std::collections::HashMap;
 
 use super::*;
 
+/// Seconds to wait before saving the use counts, so that every use does not rewrite the config
+const USES_SAVE_DELAY: f32 = 60.0;
+
 mod commands;
+mod template;
+mod triggers;
+
+use template::*;
+use triggers::*;
 
 pub struct CustomBot {
     cli: Option<Cli>,
+    shared: Shared,
+    /// Channel of the message being handled, aliases are set for it
+    channel: ChannelLogin,
     config: CustomConfig,
     commands: Commands<Self>,
+    /// Compiled patterns of the triggers by their names
+    trigger_patterns: HashMap<String, regex::Regex>,
+    /// Time left until the changed use counts are saved
+    uses_save_timer: Option<f32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -16,35 +31,253 @@ pub struct CustomSerialized {
 }
 
 impl CustomBot {
-    pub fn new_boxed(cli: &Option<Cli>) -> Box<dyn Bot> {
-        let config = match CustomConfig::load() {
+    pub fn new_boxed(cli: &Option<Cli>, shared: &Shared) -> Box<dyn Bot> {
+        let mut config = match CustomConfig::load() {
             Ok(config) => config,
             Err(error) => match error.kind() {
                 std::io::ErrorKind::NotFound => {
-                    let config = CustomConfig {
-                        commands: HashMap::new(),
-                    };
+                    let config = CustomConfig::default();
                     config.save().unwrap();
                     config
                 }
                 _ => panic!("{}", error),
             },
         };
+        if !config.counters.is_empty() {
+            for (command_name, count) in std::mem::take(&mut config.counters) {
+                if let Some(command) = config.commands.get_mut(&command_name) {
+                    command.uses += count;
+                }
+            }
+            config.save().unwrap();
+        }
+        let command_names = config.commands.keys().cloned().collect::<Vec<_>>();
         let mut bot = Self {
             cli: cli.clone(),
+            shared: shared.clone(),
+            channel: ChannelLogin::new(),
             commands: Self::commands(),
-            config: config.clone(),
+            config,
+            trigger_patterns: HashMap::new(),
+            uses_save_timer: None,
         };
-        for (command_name, _) in config.commands {
+        bot.compile_triggers();
+        for command_name in command_names {
             bot.push_command(command_name);
         }
         Box::new(bot)
     }
+
+    /// Schedules saving of the use counts
+    fn count_use(&mut self) {
+        self.uses_save_timer.get_or_insert(USES_SAVE_DELAY);
+    }
+
+    fn save_uses(&mut self) {
+        self.uses_save_timer = None;
+        if let Err(error) = self.config.save() {
+            log(
+                &self.cli,
+                LogType::Error,
+                &format!("Failed to save the use counts: {error}"),
+            );
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Drop for CustomBot {
+    fn drop(&mut self) {
+        if self.uses_save_timer.is_some() {
+            self.save_uses();
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 struct CustomConfig {
-    commands: HashMap<String, String>,
+    commands: HashMap<String, CustomCommand>,
+    /// Use counts from the older versions, moved into the commands on load
+    #[serde(default, skip_serializing)]
+    counters: HashMap<String, u64>,
+    #[serde(default)]
+    triggers: Vec<Trigger>,
+    #[serde(default)]
+    events: EventMessages,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "CustomCommandSerialized")]
+struct CustomCommand {
+    /// One of the responses is chosen randomly, see [Template] for the variables
+    responses: Vec<String>,
+    /// Minimum authority level to call the command
+    authority: AuthorityLevel,
+    /// Seconds between two calls of the command in the channel
+    cooldown: Option<f32>,
+    /// Other names of the command
+    aliases: Vec<String>,
+    enabled: bool,
+    creator: Option<String>,
+    /// Unix time of the creation
+    created: u64,
+    /// Unix time of the last change
+    edited: u64,
+    /// How many times the command has been used
+    uses: u64,
+}
+
+/// Older versions stored only the response of the command
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CustomCommandSerialized {
+    Response(String),
+    Full {
+        responses: Vec<String>,
+        #[serde(default = "default_authority")]
+        authority: AuthorityLevel,
+        #[serde(default)]
+        cooldown: Option<f32>,
+        #[serde(default)]
+        aliases: Vec<String>,
+        #[serde(default = "default_enabled")]
+        enabled: bool,
+        #[serde(default)]
+        creator: Option<String>,
+        #[serde(default = "unix_time")]
+        created: u64,
+        #[serde(default = "unix_time")]
+        edited: u64,
+        #[serde(default)]
+        uses: u64,
+    },
+}
+
+fn default_authority() -> AuthorityLevel {
+    AuthorityLevel::Viewer
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Parses the cooldown set from the chat, `0` or `none` removes the cooldown
+fn parse_cooldown(value: &str) -> Result<Option<f32>, String> {
+    if value == "none" {
+        return Ok(None);
+    }
+    let seconds = value
+        .parse::<f32>()
+        .map_err(|_| format!("{value} is not a number of seconds"))?;
+    let seconds = check_cooldown(seconds)?;
+    Ok((seconds > 0.0).then_some(seconds))
+}
+
+impl From<CustomCommandSerialized> for CustomCommand {
+    fn from(command: CustomCommandSerialized) -> Self {
+        match command {
+            CustomCommandSerialized::Response(response) => Self::new(response, None),
+            CustomCommandSerialized::Full {
+                responses,
+                authority,
+                cooldown,
+                aliases,
+                enabled,
+                creator,
+                created,
+                edited,
+                uses,
+            } => Self {
+                responses,
+                authority,
+                cooldown,
+                aliases,
+                enabled,
+                creator,
+                created,
+                edited,
+                uses,
+            },
+        }
+    }
+}
+
+impl CustomCommand {
+    fn new(response: String, creator: Option<String>) -> Self {
+        let time = unix_time();
+        Self {
+            responses: vec![response],
+            authority: AuthorityLevel::Viewer,
+            cooldown: None,
+            aliases: Vec::new(),
+            enabled: true,
+            creator,
+            created: time,
+            edited: time,
+            uses: 0,
+        }
+    }
+}
+
+/// Messages sent on chat events.
+/// `{user}` is replaced with the name of the user that caused the event,
+/// other values available depend on the event.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct EventMessages {
+    /// Values: `{months}`, `{plan}`, `{message}`
+    subscription: Option<String>,
+    /// Values: `{recipient}`, `{plan}`
+    sub_gift: Option<String>,
+    /// Values: `{viewers}`
+    raid: Option<String>,
+    /// Values: `{bits}`, `{message}`
+    cheer: Option<String>,
+    /// Message for each channel points reward id. Values: `{message}`
+    #[serde(default)]
+    redemptions: HashMap<String, String>,
+}
+
+impl EventMessages {
+    fn message(&self, event: &ChatEvent) -> Option<String> {
+        let (template, values): (_, Vec<(&str, String)>) = match event {
+            ChatEvent::Subscription {
+                months,
+                plan,
+                message,
+                ..
+            } => (
+                self.subscription.as_ref()?,
+                vec![
+                    ("months", months.to_string()),
+                    ("plan", plan.clone()),
+                    ("message", message.clone().unwrap_or_default()),
+                ],
+            ),
+            ChatEvent::SubGift {
+                recipient, plan, ..
+            } => (
+                self.sub_gift.as_ref()?,
+                vec![("recipient", recipient.clone()), ("plan", plan.clone())],
+            ),
+            ChatEvent::Raid { viewers, .. } => {
+                (self.raid.as_ref()?, vec![("viewers", viewers.to_string())])
+            }
+            ChatEvent::Cheer { bits, message, .. } => (
+                self.cheer.as_ref()?,
+                vec![("bits", bits.to_string()), ("message", message.clone())],
+            ),
+            ChatEvent::Redemption {
+                reward_id, message, ..
+            } => (
+                self.redemptions.get(reward_id)?,
+                vec![("message", message.clone())],
+            ),
+        };
+        let mut message = template.replace("{user}", event.user());
+        for (name, value) in values {
+            message = message.replace(&format!("{{{name}}}"), &value);
+        }
+        Some(message)
+    }
 }
 
 impl CustomConfig {
@@ -68,20 +301,72 @@ impl BotPerformer for CustomBot {
     fn commands(&self) -> &Commands<Self> {
         &self.commands
     }
+
+    fn cooldowns(&self) -> Vec<CommandCooldown> {
+        self.config
+            .commands
+            .iter()
+            .filter_map(|(command_name, command)| {
+                let seconds = command.cooldown?;
+                let paths = std::iter::once(command_name).chain(&command.aliases);
+                Some(CommandCooldown::new(paths).channel(seconds))
+            })
+            .collect()
+    }
 }
 
 #[async_trait]
 impl Bot for CustomBot {
     async fn handle_message(
         &mut self,
-        client: &TwitchClient,
+        shared: &Shared,
+        outbox: &Outbox,
         channel: &ChannelLogin,
         message: &CommandMessage,
     ) {
-        self.perform(&self.cli.clone(), client, channel, message)
+        self.channel = channel.clone();
+        // Triggers see the message the same way as the commands do
+        let checked = {
+            let shared = shared.lock().unwrap();
+            shared.aliases.apply(channel, message).filter(|message| {
+                !shared.permissions.is_disabled(channel, message)
+                    && !shared.permissions.is_denied(channel, message)
+            })
+        };
+        if let Some(response) = checked.and_then(|message| self.check_triggers(channel, &message)) {
+            outbox.send(channel.clone(), response);
+        }
+        self.perform(&self.cli.clone(), shared, outbox, channel, message)
             .await;
     }
 
+    async fn update(
+        &mut self,
+        _shared: &Shared,
+        _outbox: &Outbox,
+        _channel: &ChannelLogin,
+        delta_time: f32,
+    ) {
+        if let Some(timer) = &mut self.uses_save_timer {
+            *timer -= delta_time;
+            if *timer <= 0.0 {
+                self.save_uses();
+            }
+        }
+    }
+
+    async fn handle_event(
+        &mut self,
+        _shared: &Shared,
+        outbox: &Outbox,
+        channel: &ChannelLogin,
+        event: &ChatEvent,
+    ) {
+        if let Some(message) = self.config.events.message(event) {
+            outbox.send(channel.clone(), message);
+        }
+    }
+
     fn complete(
         &self,
         word: &str,
@@ -92,6 +377,10 @@ impl Bot for CustomBot {
         self.commands.complete(word, prompter, start, end)
     }
 
+    fn help(&self) -> Vec<CommandInfo> {
+        command_infos(&self.commands)
+    }
+
     fn serialize(&self) -> SerializedBot {
         SerializedBot::Custom(CustomSerialized {
             config: self.config.clone(),
@@ -0,0 +1,181 @@
+use rand::seq::SliceRandom;
+
+use super::*;
+
+/// Fields of the user record available as `{user.<field>}`
+const USER_FIELDS: [&str; 3] = ["name", "luck", "messages"];
+
+/// Response of a custom command with variables, e.g. `{sender} has used it {count} times`
+#[derive(Debug, Clone)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Text(String),
+    Variable(Variable),
+}
+
+#[derive(Debug, Clone)]
+enum Variable {
+    /// Name of the user that called the command
+    Sender,
+    /// Everything after the command name
+    Args,
+    /// Word after the command name, starting from 1
+    Arg(usize),
+    /// How many times the command has been used
+    Count,
+    /// One of the options, chosen randomly
+    Random(Vec<String>),
+    /// Time since the bot has started
+    Uptime,
+    /// Field of the caller's user record
+    User(String),
+    /// Variable registered by another bot
+    Registered(String),
+}
+
+/// Values used to fill in the template
+pub struct TemplateContext<'a> {
+    pub sender: &'a Sender,
+    pub args: &'a str,
+    pub count: u64,
+    pub shared: &'a SharedState,
+}
+
+impl Template {
+    /// Splits the text into the plain parts and the variables.
+    /// Variables registered by other bots are not checked here, see [Template::validate].
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut rest = text;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                segments.push(Segment::Text(rest[..start].to_owned()));
+            }
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| format!("Missing }} after {}", &rest[start..]))?;
+            let name = &rest[start + 1..start + end];
+            segments.push(Segment::Variable(Variable::parse(name)?));
+            rest = &rest[start + end + 1..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Text(rest.to_owned()));
+        }
+        Ok(Self { segments })
+    }
+
+    /// Checks that the variables from other bots are registered
+    pub fn validate(&self, variables: &Variables) -> Result<(), String> {
+        for segment in &self.segments {
+            if let Segment::Variable(Variable::Registered(name)) = segment {
+                if !variables.is_registered(name) {
+                    return Err(format!("Unknown variable {{{name}}}"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Fills in the variables. A value, that starts the message, cannot start with `/` or `.`,
+    /// so that the chat can not make the bot run chat commands like `/ban` through `{args}`.
+    pub fn render(&self, context: &TemplateContext) -> String {
+        let mut result = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => result += text,
+                Segment::Variable(variable) => {
+                    let value = variable.render(context);
+                    if result.trim().is_empty() {
+                        result += value.trim_start_matches(|c: char| {
+                            c.is_whitespace() || matches!(c, '/' | '.')
+                        });
+                    } else {
+                        result += &value;
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+impl Variable {
+    fn parse(name: &str) -> Result<Self, String> {
+        let name = name.trim();
+        if let Some(options) = name.strip_prefix("random:") {
+            let options = options
+                .split('|')
+                .map(|option| option.to_owned())
+                .collect::<Vec<_>>();
+            return Ok(Self::Random(options));
+        }
+        if let Some(field) = name.strip_prefix("user.") {
+            if !USER_FIELDS.contains(&field) {
+                return Err(format!(
+                    "Unknown user field {field}, expected one of {}",
+                    USER_FIELDS.join(", ")
+                ));
+            }
+            return Ok(Self::User(field.to_owned()));
+        }
+        if let Some(index) = name.strip_prefix("arg") {
+            if let Ok(index) = index.parse::<usize>() {
+                if index == 0 {
+                    return Err("Arguments are counted from {arg1}".to_owned());
+                }
+                return Ok(Self::Arg(index));
+            }
+        }
+        match name {
+            "" => Err("Empty variable {}".to_owned()),
+            "sender" => Ok(Self::Sender),
+            "args" => Ok(Self::Args),
+            "count" => Ok(Self::Count),
+            "uptime" => Ok(Self::Uptime),
+            _ => Ok(Self::Registered(name.to_owned())),
+        }
+    }
+
+    fn render(&self, context: &TemplateContext) -> String {
+        match self {
+            Self::Sender => context.sender.name.clone(),
+            Self::Args => context.args.to_owned(),
+            Self::Arg(index) => context
+                .args
+                .split_whitespace()
+                .nth(index - 1)
+                .unwrap_or("")
+                .to_owned(),
+            Self::Count => context.count.to_string(),
+            Self::Random(options) => options
+                .choose(&mut rand::thread_rng())
+                .cloned()
+                .unwrap_or_default(),
+            Self::Uptime => {
+                let minutes = context.shared.variables.uptime().as_secs() / 60;
+                if minutes >= 60 {
+                    format!("{}h {}m", minutes / 60, minutes % 60)
+                } else {
+                    format!("{minutes}m")
+                }
+            }
+            Self::User(field) => {
+                let user = match context.shared.users.find(&context.sender.name) {
+                    Some(user) => user,
+                    None => return String::new(),
+                };
+                match field.as_str() {
+                    "name" => user.name.clone(),
+                    "luck" => user.luck.unwrap_or_default().to_string(),
+                    "messages" => user.message_count.to_string(),
+                    _ => String::new(),
+                }
+            }
+            Self::Registered(name) => context.shared.variables.get(name).unwrap_or("").to_owned(),
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use regex::{Regex, RegexBuilder};
+
+use super::*;
+
+/// Response to any message matching the pattern, not only to commands
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trigger {
+    pub name: String,
+    /// Case-insensitive regular expression, e.g. `what game`
+    pub pattern: String,
+    /// See [Template] for the variables, `{args}` is the whole message
+    pub response: String,
+    /// Seconds between two responses in the channel
+    #[serde(default)]
+    pub cooldown: Option<f32>,
+    /// Minimum authority level of the sender
+    #[serde(default = "default_authority")]
+    pub authority: AuthorityLevel,
+    /// How many times the trigger has responded
+    #[serde(default)]
+    pub uses: u64,
+}
+
+pub fn compile_pattern(pattern: &str) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|error| error.to_string())
+}
+
+impl Trigger {
+    fn cooldown(&self) -> Option<CommandCooldown> {
+        let seconds = self.cooldown?;
+        Some(CommandCooldown::new([format!("trigger {}", self.name)]).channel(seconds))
+    }
+}
+
+impl CustomBot {
+    /// Compiles the patterns of the triggers, the invalid ones are skipped
+    pub fn compile_triggers(&mut self) {
+        self.trigger_patterns.clear();
+        for trigger in &self.config.triggers {
+            match compile_pattern(&trigger.pattern) {
+                Ok(regex) => {
+                    self.trigger_patterns.insert(trigger.name.clone(), regex);
+                }
+                Err(error) => log(
+                    &self.cli,
+                    LogType::Error,
+                    &format!("Invalid pattern of the trigger {}: {error}", trigger.name),
+                ),
+            }
+        }
+    }
+
+    /// Responds with the first trigger matching the message, that is not on cooldown.
+    /// The message should already have the prefix and the aliases of the channel applied.
+    pub fn check_triggers(
+        &mut self,
+        channel: &ChannelLogin,
+        message: &CommandMessage,
+    ) -> Option<String> {
+        // Commands are handled by the command trees
+        if message.message_text.starts_with('!') {
+            return None;
+        }
+        let mut shared = self.shared.lock().unwrap();
+        let trigger = self.config.triggers.iter_mut().find(|trigger| {
+            message.sender.roles.satisfies(trigger.authority)
+                && self
+                    .trigger_patterns
+                    .get(&trigger.name)
+                    .is_some_and(|regex| regex.is_match(&message.message_text))
+                && trigger.cooldown().is_none_or(|cooldown| {
+                    message.sender.roles.satisfies(cooldown.exempt)
+                        || shared
+                            .cooldowns
+                            .remaining(&cooldown, channel, &message.sender)
+                            .is_none()
+                })
+        })?;
+        if let Some(cooldown) = trigger.cooldown() {
+            shared
+                .cooldowns
+                .trigger(&cooldown, channel, &message.sender);
+        }
+        trigger.uses += 1;
+        let template = Template::parse(&trigger.response).ok()?;
+        let context = TemplateContext {
+            sender: &message.sender,
+            args: &message.message_text,
+            count: trigger.uses,
+            shared: &shared,
+        };
+        let response = template.render(&context);
+        drop(shared);
+        self.count_use();
+        Some(response)
+    }
+
+    pub(super) fn trigger_add(&mut self, name: &str, definition: &str) -> Response {
+        if self.find_trigger(name).is_some() {
+            return Some(format!("A trigger named {name} already exists").into());
+        }
+        let (pattern, response) = match definition.split_once(" => ") {
+            Some((pattern, response)) => (pattern.trim(), response.trim()),
+            None => return Some("Expected <pattern> => <response>".into()),
+        };
+        let regex = match compile_pattern(pattern) {
+            Ok(regex) => regex,
+            Err(error) => return Some(format!("Invalid pattern: {error}").into()),
+        };
+        if let Err(error) = self.check_template(response) {
+            return Some(format!("Invalid response: {error}").into());
+        }
+        self.trigger_patterns.insert(name.to_owned(), regex);
+        self.config.triggers.push(Trigger {
+            name: name.to_owned(),
+            pattern: pattern.to_owned(),
+            response: response.to_owned(),
+            cooldown: None,
+            authority: default_authority(),
+            uses: 0,
+        });
+        self.config.save().unwrap();
+        Some(format!("Added the trigger {name}: {pattern} => {response}").into())
+    }
+
+    pub(super) fn trigger_delete(&mut self, name: &str) -> Response {
+        let index = match self.find_trigger(name) {
+            Some(index) => index,
+            None => return Some(format!("There is no trigger named {name}").into()),
+        };
+        let trigger = self.config.triggers.remove(index);
+        self.trigger_patterns.remove(&trigger.name);
+        self.config.save().unwrap();
+        Some(format!("Removed the trigger {}: {}", trigger.name, trigger.pattern).into())
+    }
+
+    pub(super) fn trigger_set(&mut self, name: &str, field: &str, value: &str) -> Response {
+        let index = match self.find_trigger(name) {
+            Some(index) => index,
+            None => return Some(format!("There is no trigger named {name}").into()),
+        };
+        let value = value.trim();
+        match field {
+            "pattern" => {
+                let regex = match compile_pattern(value) {
+                    Ok(regex) => regex,
+                    Err(error) => return Some(format!("Invalid pattern: {error}").into()),
+                };
+                let trigger = &mut self.config.triggers[index];
+                trigger.pattern = value.to_owned();
+                self.trigger_patterns.insert(trigger.name.clone(), regex);
+            }
+            "response" => {
+                if let Err(error) = self.check_template(value) {
+                    return Some(format!("Invalid response: {error}").into());
+                }
+                self.config.triggers[index].response = value.to_owned();
+            }
+            "cooldown" => {
+                let cooldown = match parse_cooldown(value) {
+                    Ok(cooldown) => cooldown,
+                    Err(error) => return Some(error.into()),
+                };
+                self.config.triggers[index].cooldown = cooldown;
+            }
+            "authority" => match value.parse::<AuthorityLevel>() {
+                Ok(authority) => self.config.triggers[index].authority = authority,
+                Err(error) => return Some(error.into()),
+            },
+            _ => return Some(format!("Unknown field {field}").into()),
+        }
+        self.config.save().unwrap();
+        Some(format!("Updated {field} of the trigger {name}").into())
+    }
+
+    pub(super) fn trigger_list(&self) -> Response {
+        if self.config.triggers.is_empty() {
+            return Some("There are no triggers".into());
+        }
+        let triggers = self
+            .config
+            .triggers
+            .iter()
+            .map(|trigger| format!("{}: {}", trigger.name, trigger.pattern))
+            .collect::<Vec<_>>();
+        Some(format!("Triggers: {}", triggers.join(", ")).into())
+    }
+
+    /// Shows which triggers match the text, ignoring the cooldowns and the authority
+    pub(super) fn trigger_test(&self, text: &str) -> Response {
+        let matched = self
+            .config
+            .triggers
+            .iter()
+            .filter(|trigger| {
+                self.trigger_patterns
+                    .get(&trigger.name)
+                    .is_some_and(|regex| regex.is_match(text))
+            })
+            .map(|trigger| format!("{} ({:?} and above)", trigger.name, trigger.authority))
+            .collect::<Vec<_>>();
+        if matched.is_empty() {
+            return Some("No triggers match".into());
+        }
+        Some(format!("Matching triggers: {}", matched.join(", ")).into())
+    }
+
+    fn find_trigger(&self, name: &str) -> Option<usize> {
+        self.config
+            .triggers
+            .iter()
+            .position(|trigger| trigger.name.eq_ignore_ascii_case(name))
+    }
+}
@@ -6,41 +6,69 @@ impl BotPerformer for GamejamBot {
     fn commands(&self) -> &Commands<Self> {
         &self.commands
     }
+
+    fn cooldowns(&self) -> Vec<CommandCooldown> {
+        Self::command_cooldowns()
+    }
 }
 
 #[async_trait]
 impl Bot for GamejamBot {
     async fn handle_message(
         &mut self,
-        client: &TwitchClient,
+        shared: &Shared,
+        outbox: &Outbox,
         channel: &ChannelLogin,
         message: &CommandMessage,
     ) {
         if let Some(reply) = self.check_message(message) {
-            send_message(&self.cli, client, channel.to_owned(), reply.message).await;
+            outbox.send(channel.to_owned(), reply.message);
         }
-        self.perform(&self.cli.clone(), client, channel, message)
+        self.perform(&self.cli.clone(), shared, outbox, channel, message)
             .await;
     }
 
-    async fn update(&mut self, client: &TwitchClient, channel_login: &String, delta_time: f32) {
-        if let Some(reply) = self.update(delta_time) {
-            send_message(&self.cli, client, channel_login.clone(), reply.message).await;
+    async fn handle_event(
+        &mut self,
+        _shared: &Shared,
+        outbox: &Outbox,
+        channel: &ChannelLogin,
+        event: &ChatEvent,
+    ) {
+        let bump = match (&self.config.bump_config, event) {
+            (Some(config), ChatEvent::Redemption { reward_id, .. }) => {
+                config.reward_id.as_ref() == Some(reward_id)
+            }
+            (Some(config), ChatEvent::Cheer { bits, .. }) => {
+                config.min_bits.is_some_and(|min_bits| *bits >= min_bits)
+            }
+            _ => false,
+        };
+        if bump {
+            if let Some(reply) = self.bump(&self.find_user(event.user())) {
+                outbox.send(channel.clone(), reply.message);
+            }
         }
+    }
 
-        if self.update_sheets_queued {
-            if self.config.google_sheet_config.is_some() {
-                match self.save_sheets().await {
-                    Ok(_) => (),
-                    Err(err) => log(
-                        &self.cli,
-                        LogType::Error,
-                        &format!("Error trying to save queue into google sheets: {}", err),
-                    ),
-                }
-            }
-            self.update_sheets_queued = false;
+    async fn update(
+        &mut self,
+        shared: &Shared,
+        outbox: &Outbox,
+        channel_login: &String,
+        delta_time: f32,
+    ) {
+        if self.users_changed {
+            self.users_changed = false;
+            self.sync_users(&mut shared.lock().unwrap().users);
         }
+        self.update_variables(&mut shared.lock().unwrap().variables);
+
+        if let Some(reply) = self.update(delta_time) {
+            outbox.send(channel_login.clone(), reply.message);
+        }
+
+        self.update_sheets(delta_time).await;
     }
 
     fn complete(
@@ -53,6 +81,10 @@ impl Bot for GamejamBot {
         self.commands.complete(word, prompter, start, end)
     }
 
+    fn help(&self) -> Vec<CommandInfo> {
+        command_infos(&self.commands)
+    }
+
     fn serialize(&self) -> SerializedBot {
         SerializedBot::Gamejam(Box::new(GamejamSerialized {
             config: self.config.clone(),
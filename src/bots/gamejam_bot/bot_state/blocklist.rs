@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+
+use super::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Blocklist {
+    pub links: HashSet<String>,
+    pub domains: HashSet<String>,
+    /// Users from older versions are only known by name,
+    /// their ids are filled in once they are seen again
+    pub users: Vec<UserRef>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum BlockType {
+    Link,
+    Domain,
+    User,
+}
+
+impl Blocklist {
+    pub fn is_link_blocked(&self, link: &str) -> bool {
+        if self.links.contains(link) {
+            return true;
+        }
+        match link_domain(link) {
+            Some(domain) => self
+                .domains
+                .iter()
+                .any(|blocked| domain == *blocked || domain.ends_with(&format!(".{}", blocked))),
+            None => false,
+        }
+    }
+
+    pub fn is_user_blocked(&self, user: &UserRef) -> bool {
+        self.users.iter().any(|blocked| blocked.matches(user))
+    }
+
+    /// Returns false if the value was already blocked
+    pub fn block(&mut self, block_type: BlockType, value: &str) -> bool {
+        match block_type {
+            BlockType::Link => self.links.insert(value.to_owned()),
+            BlockType::Domain => self.domains.insert(normalize_domain(value)),
+            BlockType::User => self.block_user(UserRef::parse(value)),
+        }
+    }
+
+    /// Returns false if the value was not blocked
+    pub fn unblock(&mut self, block_type: BlockType, value: &str) -> bool {
+        match block_type {
+            BlockType::Link => self.links.remove(value),
+            BlockType::Domain => self.domains.remove(&normalize_domain(value)),
+            BlockType::User => self.unblock_user(&UserRef::parse(value)),
+        }
+    }
+
+    /// Returns false if the user was already blocked
+    pub fn block_user(&mut self, user: UserRef) -> bool {
+        if self.is_user_blocked(&user) {
+            return false;
+        }
+        self.users.push(user);
+        true
+    }
+
+    /// Returns false if the user was not blocked
+    pub fn unblock_user(&mut self, user: &UserRef) -> bool {
+        let len = self.users.len();
+        self.users.retain(|blocked| !blocked.matches(user));
+        self.users.len() != len
+    }
+
+    /// Fills in the id and the current name of the blocked user
+    pub fn update_user(&mut self, user: &UserRef) -> bool {
+        let mut changed = false;
+        for blocked in &mut self.users {
+            changed |= blocked.update(user);
+        }
+        changed
+    }
+}
+
+impl std::str::FromStr for BlockType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "link" => Ok(Self::Link),
+            "domain" => Ok(Self::Domain),
+            "user" => Ok(Self::User),
+            _ => Err(()),
+        }
+    }
+}
+
+fn normalize_domain(domain: &str) -> String {
+    domain.trim_start_matches("www.").to_lowercase()
+}
+
+/// Extracts the domain from a link, e.g. `https://www.itch.io/game` -> `itch.io`
+fn link_domain(link: &str) -> Option<String> {
+    let link = link.split_once("://").map_or(link, |(_, rest)| rest);
+    let domain = link.split(['/', '?', '#']).next()?;
+    let domain = domain.split(':').next()?.trim_start_matches("www.");
+    if domain.is_empty() {
+        None
+    } else {
+        Some(domain.to_lowercase())
+    }
+}
@@ -1,9 +1,13 @@
 use super::*;
 
+mod blocklist;
 mod queue;
+mod session;
 mod submissions;
 
+pub use blocklist::*;
 pub use queue::*;
+pub use session::*;
 pub use submissions::*;
 
 pub type Luck = u32;
@@ -15,6 +19,12 @@ pub struct GamejamState {
     pub submissions: Submissions,
     pub is_queue_open: bool,
     pub raffle_weights: HashMap<String, Luck>,
+    #[serde(skip)]
+    pub blocklist: Blocklist,
+    #[serde(default)]
+    pub session: Option<JamSession>,
+    #[serde(skip)]
+    pub history: SessionHistory,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,80 @@
+use std::collections::HashSet;
+
+use super::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JamSession {
+    pub name: String,
+    pub started: u64,
+}
+
+/// Everything that happened during a finished jam session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JamArchive {
+    pub session: JamSession,
+    pub ended: u64,
+    pub queue: Vec<Submission>,
+    pub skipped: Vec<Submission>,
+    pub pending: Vec<Submission>,
+    pub played: Vec<Submission>,
+}
+
+/// Links of the games from the archived sessions
+#[derive(Debug, Clone, Default)]
+pub struct SessionHistory {
+    pub submitted: HashSet<String>,
+    pub played: HashSet<String>,
+}
+
+impl JamArchive {
+    pub fn file_name(&self) -> String {
+        let name = self
+            .session
+            .name
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => c,
+                _ => '_',
+            })
+            .collect::<String>();
+        format!("{}_{}.json", name, self.session.started)
+    }
+}
+
+impl SessionHistory {
+    /// Loads every `.json` archive in the directory, the ones that fail to parse are skipped
+    pub fn load(
+        cli: &Option<Cli>,
+        sessions_dir: impl AsRef<std::path::Path>,
+    ) -> std::io::Result<Self> {
+        let mut history = Self::default();
+        for entry in std::fs::read_dir(sessions_dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            match load_from::<JamArchive>(&path) {
+                Ok(archive) => history.add(&archive),
+                Err(err) => log(
+                    cli,
+                    LogType::Error,
+                    &format!("Failed to load {}: {}", path.display(), err),
+                ),
+            }
+        }
+        Ok(history)
+    }
+
+    pub fn add(&mut self, archive: &JamArchive) {
+        let all_games = archive
+            .queue
+            .iter()
+            .chain(&archive.skipped)
+            .chain(&archive.pending)
+            .chain(&archive.played);
+        self.submitted
+            .extend(all_games.map(|game| game.link.clone()));
+        self.played
+            .extend(archive.played.iter().map(|game| game.link.clone()));
+    }
+}
@@ -11,6 +11,8 @@ pub struct Submissions {
     #[serde(skip)]
     pub played_games: Vec<Submission>,
     pub skipped: Vec<Submission>,
+    #[serde(default)]
+    pub pending: Vec<Submission>,
 }
 
 impl Submissions {
@@ -27,9 +29,38 @@ impl Submissions {
                 .find(|&(_, game)| predicate(game))
                 .map(|(pos, _)| pos)
                 .map(|pos| self.skipped.remove(pos))
+                .or_else(|| self.remove_pending(predicate))
         })
     }
 
+    pub fn remove_pending(
+        &mut self,
+        predicate: impl Fn(&Submission) -> bool,
+    ) -> Option<Submission> {
+        self.pending
+            .iter()
+            .position(predicate)
+            .map(|pos| self.pending.remove(pos))
+    }
+
+    /// Every game, including the played ones
+    pub fn iter(&self) -> impl Iterator<Item = &Submission> {
+        self.queue
+            .get_queue()
+            .chain(&self.skipped)
+            .chain(&self.pending)
+            .chain(&self.played_games)
+    }
+
+    /// Every game, including the played ones
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Submission> {
+        self.queue
+            .get_queue_mut()
+            .chain(&mut self.skipped)
+            .chain(&mut self.pending)
+            .chain(&mut self.played_games)
+    }
+
     pub fn find_game(
         &self,
         predicate: impl Fn(&Submission) -> bool,
@@ -46,6 +77,12 @@ impl Submissions {
             return Some((game, GameType::Skipped));
         }
 
+        // Look in the pending list
+        let game = self.pending.iter().find(|game| predicate(game));
+        if let Some(game) = game {
+            return Some((game, GameType::Pending));
+        }
+
         // Look in the played list
         let game = self.played_games.iter().find(|game| predicate(game));
         if let Some(game) = game {
@@ -71,6 +108,12 @@ impl Submissions {
             return Some((game, GameType::Skipped));
         }
 
+        // Look in the pending list
+        let game = self.pending.iter_mut().find(|game| predicate(game));
+        if let Some(game) = game {
+            return Some((game, GameType::Pending));
+        }
+
         // Look in the played list
         let game = self.played_games.iter_mut().find(|game| predicate(game));
         if let Some(game) = game {
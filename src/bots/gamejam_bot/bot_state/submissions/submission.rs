@@ -1,33 +1,44 @@
 use super::*;
 
-#[derive(Serialize, Deserialize, Clone, Copy)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 pub enum GameType {
     Queued,
     Current,
     Skipped,
     Played,
+    Pending,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 struct GameSerialized {
-    authors: Vec<String>,
+    /// Older versions stored only the names of the authors
+    authors: Vec<UserRef>,
     link: String,
+    #[serde(default)]
+    submit_time: Option<u64>,
+    #[serde(default)]
+    rating: Option<u8>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(from = "GameSerialized", into = "GameSerialized")]
 pub struct Submission {
-    pub authors: Vec<String>,
+    pub authors: Vec<UserRef>,
     pub link: String,
     pub name: Option<String>,
+    /// Unix time of the submission, unknown for the games submitted in the older versions
+    pub submit_time: Option<u64>,
+    pub rating: Option<u8>,
 }
 
 impl Submission {
-    pub fn new(authors: Vec<String>, link: String) -> Self {
+    pub fn new(authors: Vec<UserRef>, link: String) -> Self {
         Self {
             authors,
             name: Self::name_from_link(&link),
             link,
+            submit_time: Some(unix_time()),
+            rating: None,
         }
     }
 
@@ -35,6 +46,22 @@ impl Submission {
         self.name.as_ref().unwrap_or(&self.link)
     }
 
+    pub fn has_author(&self, user: &UserRef) -> bool {
+        self.authors.iter().any(|author| author.matches(user))
+    }
+
+    pub fn is_main_author(&self, user: &UserRef) -> bool {
+        self.authors[0].matches(user)
+    }
+
+    pub fn authors_names(&self) -> String {
+        self.authors
+            .iter()
+            .map(|author| author.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
     pub fn to_string_name(&self, ping: bool) -> String {
         if ping {
             format!("{} from @{}", self.name(), self.authors[0])
@@ -67,7 +94,14 @@ impl Submission {
 
 impl From<GameSerialized> for Submission {
     fn from(game: GameSerialized) -> Self {
-        Self::new(game.authors, game.link)
+        // The name is not saved, as it is derived from the link
+        Self {
+            authors: game.authors,
+            name: Self::name_from_link(&game.link),
+            link: game.link,
+            submit_time: game.submit_time,
+            rating: game.rating,
+        }
     }
 }
 
@@ -76,6 +110,20 @@ impl From<Submission> for GameSerialized {
         Self {
             authors: game.authors,
             link: game.link,
+            submit_time: game.submit_time,
+            rating: game.rating,
+        }
+    }
+}
+
+impl Display for GameType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameType::Queued => write!(f, "Queued"),
+            GameType::Current => write!(f, "Current"),
+            GameType::Skipped => write!(f, "Skipped"),
+            GameType::Played => write!(f, "Played"),
+            GameType::Pending => write!(f, "Pending"),
         }
     }
 }
@@ -63,28 +63,28 @@ impl GamejamBot {
         self.state.submissions.find_game_mut(predicate)
     }
 
-    fn remove_game_response(&mut self, author_name: &String, check_main_author: bool) -> Response {
-        match self
-            .state
-            .submissions
-            .remove_game(|game| !check_main_author || game.authors[0] == *author_name)
-        {
-            Some(_) => {
-                Some(format!("{}'s game has been removed from the queue", author_name).into())
+    fn remove_game_response(&mut self, author: &UserRef, check_main_author: bool) -> Response {
+        match self.state.submissions.remove_game(|game| {
+            if check_main_author {
+                game.is_main_author(author)
+            } else {
+                game.has_author(author)
             }
-            None => Some(format!("Couldn't find a game from {}", author_name).into()),
+        }) {
+            Some(_) => Some(format!("{}'s game has been removed from the queue", author).into()),
+            None => Some(format!("Couldn't find a game from {}", author).into()),
         }
     }
 
-    pub fn next(&mut self, author_name: Option<&String>, confirmation_required: bool) -> Response {
-        let game = match author_name {
-            Some(author_name) => match self
+    pub fn next(&mut self, author: Option<&UserRef>, confirmation_required: bool) -> Response {
+        let game = match author {
+            Some(author) => match self
                 .state
                 .submissions
-                .remove_game(|game| game.authors.contains(author_name))
+                .remove_game(|game| game.has_author(author))
             {
                 Some(game) => Ok(game),
-                None => Err(format!("Couldn't find a game from {}", author_name)),
+                None => Err(format!("Couldn't find a game from {}", author)),
             },
             None => match self.state.submissions.queue.next() {
                 Some(game) => Ok(game),
@@ -152,7 +152,7 @@ impl GamejamBot {
         Some("All games from the queue are moved to the skipped list.".into())
     }
 
-    fn unskip(&mut self, author_name: Option<&String>) -> Response {
+    fn unskip(&mut self, author: Option<&UserRef>) -> Response {
         let mut reply = String::new();
 
         let state = std::mem::take(&mut self.state.current_state);
@@ -164,8 +164,8 @@ impl GamejamBot {
             _ => (),
         }
 
-        match author_name {
-            Some(author_name) => {
+        match author {
+            Some(author) => {
                 let skipped = self
                     .state
                     .submissions
@@ -173,7 +173,7 @@ impl GamejamBot {
                     .iter()
                     .enumerate()
                     .find_map(|(index, game)| {
-                        if game.authors.contains(author_name) {
+                        if game.has_author(author) {
                             Some(index)
                         } else {
                             None
@@ -182,7 +182,7 @@ impl GamejamBot {
                     .map(|index| self.state.submissions.skipped.remove(index));
                 match self.set_current(skipped) {
                     Some(set_reply) => reply.push_str(&set_reply.message),
-                    None => reply.push_str(&format!("No game from {} found", author_name)),
+                    None => reply.push_str(&format!("No game from {} found", author)),
                 }
             }
             None => {
@@ -206,6 +206,9 @@ impl GamejamBot {
     }
 
     fn check_link(&self, game_link: &str) -> bool {
+        if self.state.blocklist.is_link_blocked(game_link) {
+            return false;
+        }
         if let Some(link_start) = &self.config.link_start {
             game_link.starts_with(link_start)
         } else {
@@ -213,19 +216,24 @@ impl GamejamBot {
         }
     }
 
-    fn submit(&mut self, game_link: String, sender: String) -> Response {
+    fn submit(&mut self, game_link: String, sender: UserRef) -> Response {
         // Check if submissions are closed
         if !self.state.is_queue_open {
             return Some("The queue is closed. You can not submit your game at the moment.".into());
         }
 
+        // Check if the sender is allowed to submit
+        if self.state.blocklist.is_user_blocked(&sender) {
+            return Some(format!("@{}, you are not allowed to submit games", sender).into());
+        }
+
         // Check if the link is legal
         if !self.check_link(&game_link) {
             return Some(format!("@{}, that link can not be submitted", sender).into());
         }
 
         // Check if the sender has already submitted a game
-        let same_author = self.find_game(|game| game.authors.contains(&sender));
+        let same_author = self.find_game(|game| game.has_author(&sender));
         if !self.config.multiple_submissions && same_author.is_some() {
             return Some(format!("@{}, you can not submit more than one game", sender).into());
         }
@@ -240,7 +248,7 @@ impl GamejamBot {
             }
 
             // Check if sender should be added as another author
-            if allow_multiple_authors_submits && !game.authors.contains(&sender) {
+            if allow_multiple_authors_submits && !game.has_author(&sender) {
                 let response = format!(
                     "@{}, you have been marked as another author of this game",
                     sender
@@ -262,39 +270,269 @@ impl GamejamBot {
                     "@{}, that game was skipped. You may return to the queue using !return command",
                     sender
                 ),
+                GameType::Pending => {
+                    format!("@{}, that game is waiting for approval.", sender)
+                }
                 _ => unreachable!(),
             };
             return Some(response.into());
         }
 
+        // Check if the game was submitted during the previous jams
+        let history = &self.state.history;
+        match self.config.resubmit_mode {
+            ResubmitMode::Always => (),
+            ResubmitMode::Unplayed => {
+                if history.played.contains(&game_link) {
+                    return Some(
+                        format!(
+                            "@{}, we have already played that game during a previous jam.",
+                            sender
+                        )
+                        .into(),
+                    );
+                }
+            }
+            ResubmitMode::Never => {
+                if history.submitted.contains(&game_link) {
+                    return Some(
+                        format!(
+                            "@{}, that game was already submitted during a previous jam.",
+                            sender
+                        )
+                        .into(),
+                    );
+                }
+            }
+        }
+
+        let game = Submission::new(vec![sender.clone()], game_link);
+
+        // Let moderators review the game first
+        if self.config.review_submissions {
+            self.state.submissions.pending.push(game);
+            self.save_games().unwrap();
+            return Some(
+                format!(
+                    "@{}, your game has been submitted and is waiting for approval!",
+                    sender
+                )
+                .into(),
+            );
+        }
+
         let response = format!("@{}, your game has been submitted!", sender).into();
 
-        self.state
-            .submissions
-            .queue
-            .queue_game(Submission::new(vec![sender], game_link));
+        self.state.submissions.queue.queue_game(game);
         self.save_games().unwrap();
 
         Some(response)
     }
 
+    fn rate(&mut self, rating: u8) -> Response {
+        match self.state.current_state.current_mut() {
+            Some(game) => {
+                game.rating = Some(rating);
+                let response = format!("{} is rated {}/10", game.name(), rating);
+                self.save_games().unwrap();
+                Some(response.into())
+            }
+            None => Some("Not playing any game at the moment".into()),
+        }
+    }
+
+    fn jam_start(&mut self, name: &str) -> Response {
+        if let Some(session) = &self.state.session {
+            return Some(
+                format!(
+                    "{} is still in progress. End it first with !jam end",
+                    session.name
+                )
+                .into(),
+            );
+        }
+
+        let name = name.trim().trim_matches('"').trim();
+        if name.is_empty() {
+            return Some("The jam needs a name: !jam start <name>".into());
+        }
+        self.state.session = Some(JamSession {
+            name: name.to_owned(),
+            started: unix_time(),
+        });
+        self.save_games().unwrap();
+        Some(format!("{} has started!", name).into())
+    }
+
+    fn jam_end(&mut self) -> std::io::Result<Response> {
+        let session = match self.state.session.take() {
+            Some(session) => session,
+            None => {
+                return Ok(Some(
+                    "No jam is in progress. Start one with !jam start <name>".into(),
+                ))
+            }
+        };
+
+        // The current game counts as played
+        self.set_current(None);
+
+        // Archive the session
+        let submissions = std::mem::take(&mut self.state.submissions);
+        let archive = JamArchive {
+            session,
+            ended: unix_time(),
+            queue: submissions.queue.get_queue().cloned().collect(),
+            skipped: submissions.skipped,
+            pending: submissions.pending,
+            played: submissions.played_games,
+        };
+        std::fs::create_dir_all(SESSIONS_DIR)?;
+        save_into(
+            &archive,
+            std::path::Path::new(SESSIONS_DIR).join(archive.file_name()),
+        )?;
+        self.state.history.add(&archive);
+
+        // Start fresh
+        self.state.raffle_weights.clear();
+        save_into(&self.state.submissions.played_games, PLAYED_GAMES_FILE)?;
+        self.save_games()?;
+
+        Ok(Some(
+            format!(
+                "{} has ended with {} games played. Thanks everyone!",
+                archive.session.name,
+                archive.played.len()
+            )
+            .into(),
+        ))
+    }
+
+    fn jam_status(&self) -> Response {
+        match &self.state.session {
+            Some(session) => Some(
+                format!(
+                    "{} is in progress, {} games played so far",
+                    session.name,
+                    self.state.submissions.played_games.len()
+                )
+                .into(),
+            ),
+            None => Some("No jam is in progress at the moment".into()),
+        }
+    }
+
+    fn approve(&mut self, author: &UserRef) -> Response {
+        match self
+            .state
+            .submissions
+            .remove_pending(|game| game.has_author(author))
+        {
+            Some(game) => {
+                let response = format!(
+                    "@{}, your game has been approved and added to the queue!",
+                    game.authors[0]
+                );
+                self.state.submissions.queue.queue_game(game);
+                self.save_games().unwrap();
+                Some(response.into())
+            }
+            None => Some(format!("No pending game from {} found", author).into()),
+        }
+    }
+
+    fn reject(&mut self, author: &UserRef, reason: Option<&str>) -> Response {
+        match self
+            .state
+            .submissions
+            .remove_pending(|game| game.has_author(author))
+        {
+            Some(game) => {
+                self.save_games().unwrap();
+                let mut response = format!("@{}, your game has been rejected", game.authors[0]);
+                match reason {
+                    Some(reason) => response.push_str(&format!(": {}", reason)),
+                    None => response.push('.'),
+                }
+                Some(response.into())
+            }
+            None => Some(format!("No pending game from {} found", author).into()),
+        }
+    }
+
+    fn pending(&self) -> Response {
+        let pending = &self.state.submissions.pending;
+        if pending.is_empty() {
+            return Some("No games are waiting for approval".into());
+        }
+        let games = pending
+            .iter()
+            .map(|game| game.to_string_link(false))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Some(
+            format!(
+                "{} games are waiting for approval: {}",
+                pending.len(),
+                games
+            )
+            .into(),
+        )
+    }
+
+    fn block(&mut self, block_type: BlockType, value: &str, block: bool) -> Response {
+        let changed = match (block_type, block) {
+            // Blocked users are matched by the id, so that they can not rename to submit again
+            (BlockType::User, true) => {
+                let user = self.find_user(value);
+                self.state.blocklist.block_user(user)
+            }
+            (BlockType::User, false) => {
+                let user = self.find_user(value);
+                self.state.blocklist.unblock_user(&user)
+            }
+            (_, true) => self.state.blocklist.block(block_type, value),
+            (_, false) => self.state.blocklist.unblock(block_type, value),
+        };
+        if !changed {
+            return Some(
+                match block {
+                    true => format!("{} is already blocked", value),
+                    false => format!("{} is not blocked", value),
+                }
+                .into(),
+            );
+        }
+        save_into(&self.state.blocklist, BLOCKLIST_FILE).unwrap();
+        Some(
+            match block {
+                true => format!("{} is now blocked", value),
+                false => format!("{} is no longer blocked", value),
+            }
+            .into(),
+        )
+    }
+
     fn edit_game(
         &mut self,
-        sender: &String,
+        sender: &UserRef,
         check_main_author: bool,
         predicate: impl Fn(&Submission) -> bool,
     ) -> Result<&mut Submission, Response> {
         let game = self.find_game_mut(predicate);
         if let Some((game, game_type)) = game {
             // Check main author
-            if check_main_author && game.authors[0] != *sender {
+            if check_main_author && !game.is_main_author(sender) {
                 return Err(Some(
                     format!("@{}, you do not have enough rights", sender).into(),
                 ));
             }
 
             match game_type {
-                GameType::Current | GameType::Queued | GameType::Skipped => return Ok(game),
+                GameType::Current | GameType::Queued | GameType::Skipped | GameType::Pending => {
+                    return Ok(game)
+                }
                 GameType::Played => {
                     return Err(Some(
                         format!("@{}, you cannot edit played games", sender).into(),
@@ -311,8 +549,8 @@ impl GamejamBot {
 
     fn authors_add(
         &mut self,
-        sender: &String,
-        other_author: String,
+        sender: &UserRef,
+        other_author: UserRef,
         check_main_author: bool,
         predicate: impl Fn(&Submission) -> bool,
     ) -> Response {
@@ -334,8 +572,8 @@ impl GamejamBot {
 
     fn authors_remove(
         &mut self,
-        sender: &String,
-        other_author: &String,
+        sender: &UserRef,
+        other_author: &UserRef,
         check_main_author: bool,
         predicate: impl Fn(&Submission) -> bool,
     ) -> Response {
@@ -357,9 +595,7 @@ impl GamejamBot {
                 let index = game
                     .authors
                     .iter()
-                    .enumerate()
-                    .find(|(_, author)| **author == *other_author)
-                    .map(|(index, _)| index);
+                    .position(|author| author.matches(other_author));
                 match index {
                     Some(index) => {
                         game.authors.remove(index);
@@ -436,7 +672,7 @@ impl GamejamBot {
         }
     }
 
-    fn raffle_join(&mut self, sender: String) -> Response {
+    fn raffle_join(&mut self, sender: UserRef) -> Response {
         if let GameJamState::Raffle { joined } = &mut self.state.current_state {
             // Find the game from sender
             // Only those who have submitted a game and whose game has not been played yet
@@ -444,13 +680,16 @@ impl GamejamBot {
             let game = self
                 .state
                 .submissions
-                .find_game(|game| game.authors.contains(&sender));
+                .find_game(|game| game.has_author(&sender));
             match game {
                 Some((game, game_type)) => match game_type {
                     GameType::Played => {
                         // The game has already been played
                         Some(format!("@{}, we have already played your game", sender).into())
                     }
+                    GameType::Pending => {
+                        Some(format!("@{}, your game has not been approved yet", sender).into())
+                    }
                     _ => {
                         let game_link = game.link.clone();
                         // Get weight
@@ -489,7 +728,7 @@ impl GamejamBot {
         }
     }
 
-    pub fn return_game(&mut self, author_name: &String) -> Response {
+    pub fn return_game(&mut self, author: &UserRef) -> Response {
         if !self.state.is_queue_open {
             return None;
         }
@@ -499,7 +738,7 @@ impl GamejamBot {
             .skipped
             .iter()
             .enumerate()
-            .find(|(_, game)| game.authors.contains(author_name))
+            .find(|(_, game)| game.has_author(author))
             .map(|(i, _)| i)
             .map(|index| {
                 let game = self.state.submissions.skipped.remove(index);
@@ -508,44 +747,53 @@ impl GamejamBot {
                     ReturnMode::Back => self.state.submissions.queue.queue_game(game),
                 }
                 self.save_games().unwrap();
-                format!("@{}, your game was returned to the queue", author_name).into()
+                format!("@{}, your game was returned to the queue", author).into()
             })
     }
 
-    fn luck(&self, author_name: &String) -> Response {
-        // Check for registered luck level
-        let luck = self.state.raffle_weights.get(author_name).copied();
+    /// Moves the game from the author to the front of the queue
+    pub fn bump(&mut self, author: &UserRef) -> Response {
+        match self
+            .state
+            .submissions
+            .queue
+            .remove_game(|game| game.has_author(author))
+        {
+            Some(game) => {
+                self.state.submissions.queue.return_game_front(game);
+                self.save_games().unwrap();
+                Some(format!("@{}, your game was moved to the front of the queue", author).into())
+            }
+            None => Some(format!("@{}, you have no game in the queue to bump", author).into()),
+        }
+    }
+
+    fn luck(&self, author: &UserRef) -> Response {
+        // Luck is registered for the game of the author
+        let game = self.find_game(|game| game.has_author(author));
 
-        let luck = match luck {
-            Some(luck) => luck,
+        let luck = match game {
             None => {
-                // Check if the viewer can join the raffle, hence their luck level is default
-                let game = self.find_game(|game| game.authors.contains(author_name));
-
-                match game {
-                    None => {
-                        return Some(
-                            format!("@{}, you need to first submit your game!", author_name).into(),
-                        );
-                    }
-                    Some((_, game_type)) => match game_type {
-                        GameType::Queued | GameType::Skipped => self.config.raffle_default_weight,
-                        _ => {
-                            return Some(
-                                (format!(
-                                    "@{}, you can no longer participate in raffles!",
-                                    author_name
-                                ))
-                                .into(),
-                            );
-                        }
-                    },
-                }
+                return Some("You need to first submit your game!".into());
             }
+            Some((game, game_type)) => match game_type {
+                GameType::Queued | GameType::Skipped => self
+                    .state
+                    .raffle_weights
+                    .get(&game.link)
+                    .copied()
+                    .unwrap_or(self.config.raffle_default_weight),
+                GameType::Pending => {
+                    return Some("Your game has not been approved yet!".into());
+                }
+                _ => {
+                    return Some("You can no longer participate in raffles!".into());
+                }
+            },
         };
 
         // Respond
-        Some(format!("@{}, your current luck level is {}", author_name, luck).into())
+        Some(format!("Your current luck level is {}", luck).into())
     }
 
     fn force(&mut self) -> Response {
@@ -559,7 +807,7 @@ impl GamejamBot {
         }
     }
 
-    fn queue(&self, sender_name: &String) -> Response {
+    fn queue(&self, sender: &UserRef) -> Response {
         let mut reply = String::new();
         if self.config.queue_mode {
             if let Some((pos, _)) = self
@@ -568,13 +816,9 @@ impl GamejamBot {
                 .queue
                 .get_queue()
                 .enumerate()
-                .find(|(_, game)| game.authors.contains(sender_name))
+                .find(|(_, game)| game.has_author(sender))
             {
-                reply.push_str(&format!(
-                    "@{}, your game is {} in the queue. ",
-                    sender_name,
-                    pos + 1
-                ));
+                reply.push_str(&format!("Your game is {} in the queue. ", pos + 1));
             }
         }
 
@@ -583,18 +827,26 @@ impl GamejamBot {
             .submissions
             .skipped
             .iter()
-            .any(|game| game.authors.contains(sender_name))
+            .any(|game| game.has_author(sender))
         {
-            reply.push_str(&format!(
-                "@{}, your game was skipped. You may return to the queue using !return command. ",
-                sender_name
-            ))
+            reply.push_str(
+                "Your game was skipped. You may return to the queue using !return command. ",
+            )
         }
 
+        let export_url = self
+            .config
+            .export_config
+            .as_ref()
+            .and_then(|config| config.public_url.as_ref());
         if let Some(config) = &self.config.google_sheet_config {
             reply.push_str(&format!("Look at the current queue at: https://docs.google.com/spreadsheets/d/{}/edit#gid=0", config.sheet_id))
+        } else if let Some(url) = export_url {
+            reply.push_str(&format!(
+                "Look at the current queue at: {}/queue",
+                url.trim_end_matches('/')
+            ))
         } else if self.config.queue_mode {
-            let mut reply = String::new();
             let games_count = self.state.submissions.queue.get_queue().count();
             if games_count == 0 {
                 reply.push_str("The queue is empty");
@@ -620,61 +872,50 @@ impl GamejamBot {
                     && bot.config.link_start.is_some()
                     && bot.check_link(&game_link)
                 {
-                    return bot.submit(game_link, sender.name.clone());
+                    return bot.submit(game_link, sender.user());
                 }
                 None
             }),
         );
 
-        let submit = CommandBuilder::<Self>::new()
-            .literal(["!submit"])
-            .word()
-            .finalize(
-                true,
-                AuthorityLevel::Viewer as usize,
-                Arc::new(|bot, sender, mut args| {
-                    let game_link = args.remove(0);
-                    bot.submit(game_link, sender.name.clone())
-                }),
-            );
+        let submit = TypedCommand::<Self>::new("!submit")
+            .url("game_link")
+            .finalize(AuthorityLevel::Viewer as usize, |bot, sender, args| {
+                bot.submit(args.text(0)?.to_owned(), sender.user())
+            });
 
         let retur = CommandBuilder::<Self>::new().literal(["!return"]).finalize(
             true,
             AuthorityLevel::Viewer as usize,
-            Arc::new(|bot, sender, _| bot.return_game(&sender.name)),
-        );
-
-        let next_queue = CommandBuilder::<Self>::new().finalize(
-            true,
-            AuthorityLevel::Broadcaster as usize,
-            Arc::new(|bot, _, _| bot.next(None, true)),
-        );
-
-        let next_author = CommandBuilder::<Self>::new().word().finalize(
-            true,
-            AuthorityLevel::Broadcaster as usize,
-            Arc::new(|bot, _, mut args| {
-                let author_name = args.remove(0);
-                bot.next(Some(&author_name), false)
-            }),
+            Arc::new(|bot, sender, _| bot.return_game(&sender.user())),
         );
 
-        let next = CommandBuilder::new()
-            .literal(["!next"])
-            .split([next_queue, next_author]);
+        let next = TypedCommand::<Self>::new("!next")
+            .user("author_name")
+            .optional()
+            .finalize(
+                AuthorityLevel::Broadcaster as usize,
+                |bot, _, args| match args.user(0) {
+                    Some(author) => {
+                        let author = bot.find_user(&author.name);
+                        bot.next(Some(&author), false)
+                    }
+                    None => bot.next(None, true),
+                },
+            );
 
         let cancel_sender = CommandBuilder::<Self>::new().finalize(
             true,
             AuthorityLevel::Viewer as usize,
-            Arc::new(|bot, sender, _| bot.remove_game_response(&sender.name, true)),
+            Arc::new(|bot, sender, _| bot.remove_game_response(&sender.user(), true)),
         );
 
         let cancel_author = CommandBuilder::<Self>::new().word().finalize(
             true,
             AuthorityLevel::Moderator as usize,
             Arc::new(|bot, _, mut args| {
-                let author_name = args.remove(0);
-                bot.remove_game_response(&author_name, false)
+                let author = bot.find_user(&args.remove(0));
+                bot.remove_game_response(&author, false)
             }),
         );
 
@@ -687,7 +928,10 @@ impl GamejamBot {
             .finalize(
                 true,
                 AuthorityLevel::Viewer as usize,
-                Arc::new(|bot, sender, _| bot.queue(&sender.name)),
+                Arc::new(|bot, sender, _| {
+                    bot.queue(&sender.user())
+                        .map(|response| response.with_delivery(Delivery::Reply))
+                }),
             );
 
         let current = CommandBuilder::<Self>::new()
@@ -703,6 +947,12 @@ impl GamejamBot {
                 }),
             );
 
+        let rate = TypedCommand::<Self>::new("!rate")
+            .integer_range("rating", 1, 10)
+            .finalize(AuthorityLevel::Broadcaster as usize, |bot, _, args| {
+                bot.rate(args.integer(0)? as u8)
+            });
+
         let skip_next = CommandBuilder::<Self>::new().literal(["next"]).finalize(
             true,
             AuthorityLevel::Broadcaster as usize,
@@ -726,24 +976,13 @@ impl GamejamBot {
                 .literal(["!skip"])
                 .split([skip_next, skip_all, skip_current]);
 
-        let unskip_last = CommandBuilder::<Self>::new().finalize(
-            true,
-            AuthorityLevel::Broadcaster as usize,
-            Arc::new(|bot, _, _| bot.unskip(None)),
-        );
-
-        let unskip_author = CommandBuilder::<Self>::new().word().finalize(
-            true,
-            AuthorityLevel::Broadcaster as usize,
-            Arc::new(|bot, _, mut args| {
-                let author_name = args.remove(0);
-                bot.unskip(Some(&author_name))
-            }),
-        );
-
-        let unskip = CommandBuilder::new()
-            .literal(["!unskip"])
-            .split([unskip_last, unskip_author]);
+        let unskip = TypedCommand::<Self>::new("!unskip")
+            .user("author_name")
+            .optional()
+            .finalize(AuthorityLevel::Broadcaster as usize, |bot, _, args| {
+                let author = args.user(0).map(|author| bot.find_user(&author.name));
+                bot.unskip(author.as_ref())
+            });
 
         let stop = CommandBuilder::<Self>::new().literal(["!stop"]).finalize(
             true,
@@ -807,13 +1046,16 @@ impl GamejamBot {
         let join = CommandBuilder::<Self>::new().literal(["!join"]).finalize(
             true,
             AuthorityLevel::Viewer as usize,
-            Arc::new(|bot, sender, _| bot.raffle_join(sender.name.clone())),
+            Arc::new(|bot, sender, _| bot.raffle_join(sender.user())),
         );
 
         let luck = CommandBuilder::<Self>::new().literal(["!luck"]).finalize(
             true,
             AuthorityLevel::Viewer as usize,
-            Arc::new(|bot, sender, _| bot.luck(&sender.name)),
+            Arc::new(|bot, sender, _| {
+                bot.luck(&sender.user())
+                    .map(|response| response.with_delivery(Delivery::Whisper))
+            }),
         );
 
         let authors_add = CommandBuilder::new().literal(["add"]).word().split([
@@ -822,8 +1064,8 @@ impl GamejamBot {
                 AuthorityLevel::Moderator as usize,
                 Arc::new(|bot, sender, mut args| {
                     let game_link = args.remove(0);
-                    let other_author = args.remove(0);
-                    bot.authors_add(&sender.name, other_author, false, |game| {
+                    let other_author = bot.find_user(&args.remove(0));
+                    bot.authors_add(&sender.user(), other_author, false, |game| {
                         game.link == game_link
                     })
                 }),
@@ -832,10 +1074,9 @@ impl GamejamBot {
                 true,
                 AuthorityLevel::Viewer as usize,
                 Arc::new(|bot, sender, mut args| {
-                    let other_author = args.remove(0);
-                    bot.authors_add(&sender.name, other_author, true, |game| {
-                        game.authors.contains(&sender.name)
-                    })
+                    let sender = sender.user();
+                    let other_author = bot.find_user(&args.remove(0));
+                    bot.authors_add(&sender, other_author, true, |game| game.has_author(&sender))
                 }),
             ),
         ]);
@@ -846,8 +1087,8 @@ impl GamejamBot {
                 AuthorityLevel::Moderator as usize,
                 Arc::new(|bot, sender, mut args| {
                     let game_link = args.remove(0);
-                    let other_author = args.remove(0);
-                    bot.authors_remove(&sender.name, &other_author, false, |game| {
+                    let other_author = bot.find_user(&args.remove(0));
+                    bot.authors_remove(&sender.user(), &other_author, false, |game| {
                         game.link == game_link
                     })
                 }),
@@ -856,9 +1097,10 @@ impl GamejamBot {
                 true,
                 AuthorityLevel::Viewer as usize,
                 Arc::new(|bot, sender, mut args| {
-                    let other_author = args.remove(0);
-                    bot.authors_remove(&sender.name, &other_author, true, |game| {
-                        game.authors.contains(&sender.name)
+                    let sender = sender.user();
+                    let other_author = bot.find_user(&args.remove(0));
+                    bot.authors_remove(&sender, &other_author, true, |game| {
+                        game.has_author(&sender)
                     })
                 }),
             ),
@@ -868,6 +1110,79 @@ impl GamejamBot {
             .literal(["!authors"])
             .split([authors_add, authors_remove]);
 
+        let jam_start = CommandBuilder::<Self>::new()
+            .literal(["start"])
+            .line()
+            .finalize(
+                true,
+                AuthorityLevel::Broadcaster as usize,
+                Arc::new(|bot, _, args| bot.jam_start(&args[0])),
+            );
+
+        let jam_end = CommandBuilder::<Self>::new().literal(["end"]).finalize(
+            true,
+            AuthorityLevel::Broadcaster as usize,
+            Arc::new(|bot, _, _| match bot.jam_end() {
+                Ok(response) => response,
+                Err(err) => {
+                    log(
+                        &bot.cli,
+                        LogType::Error,
+                        &format!("Failed to archive the jam: {err}"),
+                    );
+                    Some("Failed to archive the jam".into())
+                }
+            }),
+        );
+
+        let jam_status = CommandBuilder::<Self>::new().finalize(
+            true,
+            AuthorityLevel::Viewer as usize,
+            Arc::new(|bot, _, _| bot.jam_status()),
+        );
+
+        let jam = CommandBuilder::new()
+            .literal(["!jam"])
+            .split([jam_start, jam_end, jam_status]);
+
+        let approve = TypedCommand::<Self>::new("!approve")
+            .user("author_name")
+            .finalize(AuthorityLevel::Moderator as usize, |bot, _, args| {
+                let author = bot.find_user(&args.user(0)?.name);
+                bot.approve(&author)
+            });
+
+        let reject = TypedCommand::<Self>::new("!reject")
+            .user("author_name")
+            .line("reason")
+            .optional()
+            .finalize(AuthorityLevel::Moderator as usize, |bot, _, args| {
+                let author = bot.find_user(&args.user(0)?.name);
+                bot.reject(&author, args.text(1))
+            });
+
+        let pending = CommandBuilder::<Self>::new()
+            .literal(["!pending"])
+            .finalize(
+                true,
+                AuthorityLevel::Moderator as usize,
+                Arc::new(|bot, _, _| bot.pending()),
+            );
+
+        let block = CommandBuilder::<Self>::new()
+            .choice(["!block", "!unblock"])
+            .choice(["link", "domain", "user"])
+            .word()
+            .finalize(
+                true,
+                AuthorityLevel::Moderator as usize,
+                Arc::new(|bot, _, args| {
+                    let block = args[0] == "!block";
+                    let block_type = args[1].parse().ok()?;
+                    bot.block(block_type, &args[2], block)
+                }),
+            );
+
         Commands {
             commands: vec![
                 direct_submit,
@@ -877,6 +1192,7 @@ impl GamejamBot {
                 cancel,
                 list,
                 current,
+                rate,
                 skip,
                 unskip,
                 stop,
@@ -887,7 +1203,21 @@ impl GamejamBot {
                 join,
                 luck,
                 authors,
+                jam,
+                approve,
+                reject,
+                pending,
+                block,
             ],
         }
     }
+
+    pub fn command_cooldowns() -> Vec<CommandCooldown> {
+        vec![
+            // The reply is personal, so every viewer can ask once in a while
+            CommandCooldown::new(["!queue", "!list"]).user(15.0),
+            CommandCooldown::new(["!current"]).channel(15.0).silent(),
+            CommandCooldown::new(["!luck"]).user(30.0),
+        ]
+    }
 }
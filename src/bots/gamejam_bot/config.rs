@@ -3,6 +3,8 @@ use super::*;
 const CONFIG_FILE: &str = "config/gamejam/gamejam_config.json";
 pub const SAVE_FILE: &str = "config/gamejam/gamejam_nertsalbot.json";
 pub const PLAYED_GAMES_FILE: &str = "config/gamejam/games_played.json";
+pub const BLOCKLIST_FILE: &str = "config/gamejam/blocklist.json";
+pub const SESSIONS_DIR: &str = "config/gamejam/sessions";
 
 macro_rules! load {
     ( $path: expr ) => {
@@ -22,6 +24,24 @@ pub enum ReturnMode {
     Front,
 }
 
+/// Which games from the previous jam sessions may be submitted again
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+pub enum ResubmitMode {
+    Always,
+    #[default]
+    Unplayed,
+    Never,
+}
+
+/// Lets viewers move their game to the front of the queue
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BumpConfig {
+    /// Id of the channel points reward
+    pub reward_id: Option<String>,
+    /// Minimum number of bits in a single cheer
+    pub min_bits: Option<u64>,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct GamejamConfig {
     pub multiple_submissions: bool,
@@ -33,11 +53,19 @@ pub struct GamejamConfig {
     pub allow_direct_link_submit: bool,
     pub allow_multiple_authors_submit: bool,
     pub raffle_default_weight: u32,
+    #[serde(default)]
+    pub review_submissions: bool,
+    #[serde(default)]
+    pub resubmit_mode: ResubmitMode,
     pub google_sheet_config: Option<GoogleSheetConfig>,
+    #[serde(default)]
+    pub export_config: Option<ExportConfig>,
+    #[serde(default)]
+    pub bump_config: Option<BumpConfig>,
 }
 
 impl GamejamBot {
-    pub fn new_boxed(cli: &Option<Cli>) -> Box<dyn Bot> {
+    pub fn new_boxed(cli: &Option<Cli>, shared: &Shared) -> Box<dyn Bot> {
         // Read config
         let config: GamejamConfig = serde_json::from_reader(std::io::BufReader::new(
             std::fs::File::open(CONFIG_FILE).unwrap(),
@@ -50,18 +78,32 @@ impl GamejamBot {
         // Load played games
         state.submissions.played_games = load!(PLAYED_GAMES_FILE);
 
+        // Load blocklist
+        state.blocklist = load!(BLOCKLIST_FILE);
+
+        // Load games from the previous jam sessions
+        state.history = match SessionHistory::load(cli, SESSIONS_DIR) {
+            Ok(history) => history,
+            Err(err) => match err.kind() {
+                std::io::ErrorKind::NotFound => Default::default(),
+                _ => panic!("Error loading {}: {}", SESSIONS_DIR, err),
+            },
+        };
+
         // Initialize bot
         let mut bot = Self {
             cli: cli.clone(),
             config,
             commands: Self::commands(),
+            shared: shared.clone(),
+            users_changed: true,
             hub: None,
-            update_sheets_queued: true,
+            sheets: SheetsSync::new(),
             state,
         };
 
         // Initialize google sheets
-        if bot.config.google_sheet_config.is_some() {
+        if let Some(sheet_config) = &bot.config.google_sheet_config {
             let service_key: oauth2::ServiceAccountKey = serde_json::from_reader(
                 std::io::BufReader::new(std::fs::File::open("secrets/service_key.json").unwrap()),
             )
@@ -71,16 +113,32 @@ impl GamejamBot {
             )
             .expect("Failed to create an authenticator for google service");
 
-            bot.hub = Some(Sheets::new(
-                hyper::Client::builder().build(
-                    hyper_rustls::HttpsConnectorBuilder::new()
-                        .with_native_roots()
-                        .https_only()
-                        .enable_http1()
-                        .build(),
-                ),
+            // Allow plain http only for a custom server
+            let connector = hyper_rustls::HttpsConnectorBuilder::new().with_native_roots();
+            let connector = match &sheet_config.api_base_url {
+                Some(_) => connector.https_or_http(),
+                None => connector.https_only(),
+            };
+            let mut hub = Sheets::new(
+                hyper::Client::builder().build(connector.enable_http1().build()),
                 auth,
-            ));
+            );
+            if let Some(base_url) = &sheet_config.api_base_url {
+                hub.base_url(base_url.clone());
+                hub.root_url(base_url.clone());
+            }
+            bot.hub = Some(hub);
+        }
+
+        bot.update_variables(&mut shared.lock().unwrap().variables);
+
+        // Export the loaded queue
+        if let Err(err) = bot.export_queue() {
+            log(
+                &bot.cli,
+                LogType::Error,
+                &format!("Failed to export the queue: {}", err),
+            );
         }
 
         Box::new(bot)
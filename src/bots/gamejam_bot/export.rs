@@ -0,0 +1,221 @@
+use std::fmt::Write;
+
+use super::*;
+
+const EXPORT_DIR: &str = "status";
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ExportConfig {
+    /// Address, at which the server can be reached by viewers, e.g. `https://example.com`
+    pub public_url: Option<String>,
+    /// Falls back to the google sheet's cell format
+    #[serde(default)]
+    cell_format: Option<GoogleSheetCellFormat>,
+}
+
+struct ExportSection<'a> {
+    title: &'static str,
+    game_type: GameType,
+    games: Vec<&'a Submission>,
+}
+
+impl GamejamBot {
+    pub fn export_queue(&self) -> std::io::Result<()> {
+        let export_config = match &self.config.export_config {
+            Some(config) => config,
+            None => return Ok(()),
+        };
+        let cell_format = export_config.cell_format.as_ref().or_else(|| {
+            self.config
+                .google_sheet_config
+                .as_ref()
+                .map(|config| &config.cell_format)
+        });
+
+        let sections = self.export_sections();
+        let path = std::path::Path::new(EXPORT_DIR);
+        std::fs::create_dir_all(path)?;
+        std::fs::write(path.join("queue.csv"), self.export_csv(&sections))?;
+        std::fs::write(path.join("queue.md"), self.export_markdown(&sections))?;
+        std::fs::write(
+            path.join("queue.html"),
+            self.export_html(&sections, cell_format),
+        )?;
+        Ok(())
+    }
+
+    fn export_sections(&self) -> Vec<ExportSection<'_>> {
+        vec![
+            ExportSection {
+                title: "Current",
+                game_type: GameType::Current,
+                games: self.state.current_state.current().into_iter().collect(),
+            },
+            ExportSection {
+                title: "Queue",
+                game_type: GameType::Queued,
+                games: self.state.submissions.queue.get_queue().collect(),
+            },
+            ExportSection {
+                title: "Skipped",
+                game_type: GameType::Skipped,
+                games: self.state.submissions.skipped.iter().collect(),
+            },
+            ExportSection {
+                title: "Played",
+                game_type: GameType::Played,
+                games: self.state.submissions.played_games.iter().collect(),
+            },
+        ]
+    }
+
+    fn export_luck(&self, game: &Submission, game_type: GameType) -> String {
+        self.game_luck(game, game_type)
+            .map(|luck| luck.to_string())
+            .unwrap_or_default()
+    }
+
+    fn export_csv(&self, sections: &[ExportSection<'_>]) -> String {
+        let mut csv = String::from("status,name,link,authors,luck\n");
+        for section in sections {
+            for game in &section.games {
+                let values: [&str; 5] = [
+                    section.title,
+                    game.name(),
+                    &game.link,
+                    &game.authors_names(),
+                    &self.export_luck(game, section.game_type),
+                ];
+                let values = values.map(csv_escape);
+                writeln!(csv, "{}", values.join(",")).unwrap();
+            }
+        }
+        csv
+    }
+
+    fn export_markdown(&self, sections: &[ExportSection<'_>]) -> String {
+        let mut markdown = String::from("# Game queue\n");
+        for section in sections {
+            writeln!(
+                markdown,
+                "\n## {} ({})\n",
+                section.title,
+                section.games.len()
+            )
+            .unwrap();
+            if section.games.is_empty() {
+                writeln!(markdown, "No games").unwrap();
+                continue;
+            }
+            writeln!(markdown, "| # | Game | Authors | Luck |").unwrap();
+            writeln!(markdown, "|---|------|---------|------|").unwrap();
+            for (index, game) in section.games.iter().enumerate() {
+                let name = markdown_escape(game.name());
+                let name = if is_web_link(&game.link) {
+                    format!("[{}]({})", name, game.link)
+                } else {
+                    name
+                };
+                writeln!(
+                    markdown,
+                    "| {} | {} | {} | {} |",
+                    index + 1,
+                    name,
+                    markdown_escape(&game.authors_names()),
+                    self.export_luck(game, section.game_type),
+                )
+                .unwrap();
+            }
+        }
+        markdown
+    }
+
+    fn export_html(
+        &self,
+        sections: &[ExportSection<'_>],
+        cell_format: Option<&GoogleSheetCellFormat>,
+    ) -> String {
+        let mut html = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+            <meta http-equiv=\"refresh\" content=\"30\">\n<title>Game queue</title>\n\
+            <style>\n\
+            body { font-family: sans-serif; margin: 2em; }\n\
+            table { border-collapse: collapse; margin-bottom: 2em; }\n\
+            th, td { border: 1px solid #888; padding: 0.3em 0.8em; text-align: left; }\n\
+            </style>\n</head>\n<body>\n<h1>Game queue</h1>\n",
+        );
+        for section in sections {
+            writeln!(html, "<h2>{} ({})</h2>", section.title, section.games.len()).unwrap();
+            if section.games.is_empty() {
+                writeln!(html, "<p>No games</p>").unwrap();
+                continue;
+            }
+            let style = cell_format
+                .and_then(|format| format.color(section.game_type))
+                .map(|color| format!(" style=\"background-color: {}\"", html_color(color)))
+                .unwrap_or_default();
+            writeln!(
+                html,
+                "<table>\n<tr><th>#</th><th>Game</th><th>Authors</th><th>Luck</th></tr>"
+            )
+            .unwrap();
+            for (index, game) in section.games.iter().enumerate() {
+                let name = html_escape(game.name());
+                let name = if is_web_link(&game.link) {
+                    format!("<a href=\"{}\">{}</a>", html_escape(&game.link), name)
+                } else {
+                    name
+                };
+                writeln!(
+                    html,
+                    "<tr{}><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    style,
+                    index + 1,
+                    name,
+                    html_escape(&game.authors_names()),
+                    self.export_luck(game, section.game_type),
+                )
+                .unwrap();
+            }
+            writeln!(html, "</table>").unwrap();
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+/// Only web links are clickable, so that e.g. `javascript:` links from older saves are not
+fn is_web_link(link: &str) -> bool {
+    let link = link.to_ascii_lowercase();
+    link.starts_with("https://") || link.starts_with("http://")
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+fn markdown_escape(value: &str) -> String {
+    value.replace('|', "\\|")
+}
+
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn html_color(color: &google_sheets4::api::Color) -> String {
+    let channel = |value: Option<f32>| (value.unwrap_or(0.0).clamp(0.0, 1.0) * 255.0) as u8;
+    format!(
+        "rgb({}, {}, {})",
+        channel(color.red),
+        channel(color.green),
+        channel(color.blue)
+    )
+}
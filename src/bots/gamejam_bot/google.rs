@@ -1,129 +1,454 @@
 use super::*;
 
+const RETRY_DELAY_MIN: f32 = 2.0;
+const RETRY_DELAY_MAX: f32 = 120.0;
+/// Longest time (in seconds) an update can be postponed by new changes
+const UPDATE_WAIT_MAX: f32 = 30.0;
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct GoogleSheetConfig {
     pub sheet_id: String,
     display_luck: bool,
-    cell_format: GoogleSheetCellFormat,
+    pub cell_format: GoogleSheetCellFormat,
+    /// Delay (in seconds) between a change and the sheet update,
+    /// so that multiple changes are sent in one request
+    #[serde(default = "default_update_delay")]
+    update_delay: f32,
+    /// Overrides the Google Sheets API url, e.g. to use a local server
+    #[serde(default)]
+    pub api_base_url: Option<String>,
+    /// Defaults to the game link, the authors and, if `display_luck` is set, luck
+    #[serde(default)]
+    columns: Option<Vec<SheetColumn>>,
+    #[serde(default)]
+    tabs: SheetTabs,
+    /// Whether to display the number of games in each category above the header
+    #[serde(default)]
+    summary_row: bool,
+    /// If set, played games are put into this spreadsheet instead
+    #[serde(default)]
+    history_sheet_id: Option<String>,
+}
+
+fn default_update_delay() -> f32 {
+    5.0
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+enum SheetColumn {
+    Name,
+    Link,
+    Authors,
+    Luck,
+    SubmitTime,
+    Lane,
+    Rating,
+}
+
+/// Tabs are identified by their id (the `gid` in the url)
+#[derive(Clone, Copy, Serialize, Deserialize)]
+enum SheetTabs {
+    /// Every game is put into one tab
+    Single { tab: i32 },
+    /// Every category gets its own tab, the current game is put into the queue tab
+    Separate {
+        queue: i32,
+        skipped: i32,
+        played: i32,
+    },
+}
+
+impl Default for SheetTabs {
+    fn default() -> Self {
+        Self::Single { tab: 0 }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
-struct GoogleSheetCellFormat {
+pub struct GoogleSheetCellFormat {
     color_queued: Option<google_sheets4::api::Color>,
     color_current: Option<google_sheets4::api::Color>,
     color_skipped: Option<google_sheets4::api::Color>,
     color_played: Option<google_sheets4::api::Color>,
+    #[serde(default)]
+    color_pending: Option<google_sheets4::api::Color>,
+}
+
+impl GoogleSheetCellFormat {
+    pub fn color(&self, game_type: GameType) -> Option<&google_sheets4::api::Color> {
+        match game_type {
+            GameType::Queued => self.color_queued.as_ref(),
+            GameType::Current => self.color_current.as_ref(),
+            GameType::Skipped => self.color_skipped.as_ref(),
+            GameType::Played => self.color_played.as_ref(),
+            GameType::Pending => self.color_pending.as_ref(),
+        }
+    }
+}
+
+/// Keeps track of what has been written into the sheets
+pub struct SheetsSync {
+    /// Rows that are currently in the sheets, missing if unknown
+    rows: HashMap<SheetTarget, Vec<SheetRow>>,
+    /// Time left until the next update, or None if there is nothing to update
+    update_timer: Option<f32>,
+    /// Time left until the pending update can no longer be postponed
+    max_wait: Option<f32>,
+    retry_delay: f32,
+}
+
+/// A tab in a spreadsheet
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct SheetTarget {
+    spreadsheet_id: String,
+    tab_id: i32,
+}
+
+#[derive(Clone, PartialEq)]
+struct SheetRow {
+    values: Vec<String>,
+    /// None for the header and the summary rows
+    game_type: Option<GameType>,
+}
+
+type SheetTable = (SheetTarget, Vec<SheetRow>);
+
+impl SheetsSync {
+    pub fn new() -> Self {
+        Self {
+            rows: HashMap::new(),
+            update_timer: Some(0.0),
+            max_wait: None,
+            retry_delay: RETRY_DELAY_MIN,
+        }
+    }
+
+    /// Schedules an update, postponing the one already scheduled,
+    /// but not for longer than [UPDATE_WAIT_MAX] in total
+    pub fn queue_update(&mut self, delay: f32) {
+        self.update_timer = Some(match self.update_timer {
+            // Do not cut the wait after a failed update short
+            Some(timer) if self.retry_delay > RETRY_DELAY_MIN => timer.max(delay),
+            _ => {
+                let max_wait = *self.max_wait.get_or_insert(delay.max(UPDATE_WAIT_MAX));
+                delay.min(max_wait)
+            }
+        });
+    }
+
+    /// Returns true when it is time to update the sheets
+    pub fn tick(&mut self, delta_time: f32) -> bool {
+        if let Some(max_wait) = &mut self.max_wait {
+            *max_wait -= delta_time;
+        }
+        match &mut self.update_timer {
+            Some(timer) => {
+                *timer -= delta_time;
+                *timer <= 0.0
+            }
+            None => false,
+        }
+    }
+
+    fn succeeded(&mut self, tables: Vec<SheetTable>) {
+        self.rows.extend(tables);
+        self.update_timer = None;
+        self.max_wait = None;
+        self.retry_delay = RETRY_DELAY_MIN;
+    }
+
+    /// Returns the delay before the next attempt
+    fn failed(&mut self, retry: bool) -> Option<f32> {
+        // The sheets might be in any state now
+        self.rows.clear();
+        self.max_wait = None;
+        if retry {
+            let delay = self.retry_delay;
+            self.retry_delay = (self.retry_delay * 2.0).min(RETRY_DELAY_MAX);
+            self.update_timer = Some(delay);
+            Some(delay)
+        } else {
+            self.update_timer = None;
+            None
+        }
+    }
 }
 
 impl GamejamBot {
-    pub async fn save_sheets(&self) -> google_sheets4::Result<()> {
-        use google_sheets4::api::*;
+    pub fn queue_sheets_update(&mut self) {
+        if let Some(config) = &self.config.google_sheet_config {
+            self.sheets.queue_update(config.update_delay);
+        }
+    }
 
-        // Headers
-        let mut rows = Vec::new();
-        let mut values = vec!["Game link".to_owned(), "Author".to_owned()];
-        if self
-            .config
-            .google_sheet_config
-            .as_ref()
-            .unwrap()
-            .display_luck
-        {
-            values.push("Luck".to_owned());
-        }
-        rows.push(self.values_to_row_data(
-            values,
-            Some(CellFormat {
-                text_format: Some(TextFormat {
-                    bold: Some(true),
-                    ..Default::default()
-                }),
-                ..Default::default()
-            }),
-        ));
+    pub async fn update_sheets(&mut self, delta_time: f32) {
+        if self.config.google_sheet_config.is_none() || !self.sheets.tick(delta_time) {
+            return;
+        }
 
-        // Current game
-        let current_game = match &self.state.current_state {
-            GameJamState::Playing { game } | GameJamState::Waiting { game, .. } => Some(game),
-            _ => None,
-        };
-        if let Some(game) = current_game {
-            rows.push(self.values_to_row_data(
-                self.game_to_values(game),
-                self.game_to_format(GameType::Current),
-            ));
+        let tables = self.sheet_tables();
+        let mut spreadsheets = tables
+            .iter()
+            .map(|(target, _)| target.spreadsheet_id.as_str())
+            .collect::<Vec<_>>();
+        spreadsheets.sort_unstable();
+        spreadsheets.dedup();
+
+        for spreadsheet_id in spreadsheets {
+            let spreadsheet_tables = tables
+                .iter()
+                .filter(|(target, _)| target.spreadsheet_id == spreadsheet_id);
+            if let Err(err) = self.save_sheets(spreadsheet_id, spreadsheet_tables).await {
+                let retry = self.sheets.failed(is_quota_error(&err));
+                let message = match retry {
+                    Some(delay) => format!(
+                        "Google Sheets quota exceeded, retrying in {} seconds: {}",
+                        delay, err
+                    ),
+                    None => format!("Error trying to save queue into google sheets: {}", err),
+                };
+                log(&self.cli, LogType::Error, &message);
+                return;
+            }
         }
 
-        // Queued games
-        for game in self.state.submissions.queue.get_queue() {
-            let mut values = self.game_to_values(game);
-            if let Some(sheet_config) = &self.config.google_sheet_config {
+        self.sheets.succeeded(tables);
+    }
+
+    fn sheet_tables(&self) -> Vec<SheetTable> {
+        let sheet_config = self.config.google_sheet_config.as_ref().unwrap();
+
+        // Distribute the games between the tabs
+        let current = self
+            .state
+            .current_state
+            .current()
+            .map(|game| (game, GameType::Current));
+        let queued = self
+            .state
+            .submissions
+            .queue
+            .get_queue()
+            .map(|game| (game, GameType::Queued));
+        let skipped = self
+            .state
+            .submissions
+            .skipped
+            .iter()
+            .map(|game| (game, GameType::Skipped));
+        let played = self
+            .state
+            .submissions
+            .played_games
+            .iter()
+            .map(|game| (game, GameType::Played));
+
+        let (queue_tab, skipped_tab, played_tab) = match sheet_config.tabs {
+            SheetTabs::Single { tab } => match &sheet_config.history_sheet_id {
+                Some(_) => (tab, tab, 0),
+                None => (tab, tab, tab),
+            },
+            SheetTabs::Separate {
+                queue,
+                skipped,
+                played,
+            } => (queue, skipped, played),
+        };
+        let target = |tab_id| SheetTarget {
+            spreadsheet_id: sheet_config.sheet_id.clone(),
+            tab_id,
+        };
+        let history_target = SheetTarget {
+            spreadsheet_id: sheet_config
+                .history_sheet_id
+                .as_ref()
+                .unwrap_or(&sheet_config.sheet_id)
+                .clone(),
+            tab_id: played_tab,
+        };
+
+        let mut tables = Vec::new();
+        add_games(
+            &mut tables,
+            target(queue_tab),
+            current.into_iter().chain(queued),
+        );
+        add_games(&mut tables, target(skipped_tab), skipped);
+        add_games(&mut tables, history_target, played);
+
+        // Headers
+        let columns = match &sheet_config.columns {
+            Some(columns) => columns.clone(),
+            None => {
+                let mut columns = vec![SheetColumn::Link, SheetColumn::Authors];
                 if sheet_config.display_luck {
-                    values.push(
-                        self.state
-                            .raffle_weights
-                            .get(&game.link)
-                            .copied()
-                            .unwrap_or(self.config.raffle_default_weight)
-                            .to_string(),
-                    )
+                    columns.push(SheetColumn::Luck);
                 }
+                columns
             }
-            rows.push(self.values_to_row_data(values, self.game_to_format(GameType::Queued)));
+        };
+        let mut header_rows = Vec::new();
+        if sheet_config.summary_row {
+            let submissions = &self.state.submissions;
+            header_rows.push(SheetRow {
+                values: vec![
+                    format!("Queued: {}", submissions.queue.get_queue().count()),
+                    format!("Skipped: {}", submissions.skipped.len()),
+                    format!("Played: {}", submissions.played_games.len()),
+                ],
+                game_type: None,
+            });
         }
+        header_rows.push(SheetRow {
+            values: columns
+                .iter()
+                .map(|column| column.header().to_owned())
+                .collect(),
+            game_type: None,
+        });
 
-        for game in &self.state.submissions.skipped {
-            rows.push(self.values_to_row_data(
-                self.game_to_values(game),
-                self.game_to_format(GameType::Skipped),
-            ));
-        }
-        for game in &self.state.submissions.played_games {
-            rows.push(self.values_to_row_data(
-                self.game_to_values(game),
-                self.game_to_format(GameType::Played),
-            ));
+        tables
+            .into_iter()
+            .map(|(target, games)| {
+                let mut rows = header_rows.clone();
+                rows.extend(games.into_iter().map(|(game, game_type)| {
+                    SheetRow {
+                        values: columns
+                            .iter()
+                            .map(|&column| self.column_value(column, game, game_type))
+                            .collect(),
+                        game_type: Some(game_type),
+                    }
+                }));
+
+                // Fill empty cells, so that they get cleared when rows are updated
+                let width = rows.iter().map(|row| row.values.len()).max().unwrap_or(0);
+                for row in &mut rows {
+                    row.values.resize(width, String::new());
+                }
+
+                (target, rows)
+            })
+            .collect()
+    }
+
+    fn column_value(&self, column: SheetColumn, game: &Submission, game_type: GameType) -> String {
+        match column {
+            SheetColumn::Name => game.name().to_owned(),
+            SheetColumn::Link => game.link.clone(),
+            SheetColumn::Authors => game.authors_names(),
+            SheetColumn::Luck => self
+                .game_luck(game, game_type)
+                .map(|luck| luck.to_string())
+                .unwrap_or_default(),
+            SheetColumn::SubmitTime => game.submit_time.map(format_time).unwrap_or_default(),
+            SheetColumn::Lane => game_type.to_string(),
+            SheetColumn::Rating => game
+                .rating
+                .map(|rating| rating.to_string())
+                .unwrap_or_default(),
         }
+    }
 
-        let update_values = BatchUpdateSpreadsheetRequest {
-            requests: Some(vec![
-                Request {
-                    update_sheet_properties: Some(UpdateSheetPropertiesRequest {
-                        properties: Some(SheetProperties {
-                            grid_properties: Some(GridProperties {
-                                frozen_row_count: Some(1),
+    async fn save_sheets(
+        &self,
+        spreadsheet_id: &str,
+        tables: impl Iterator<Item = &SheetTable>,
+    ) -> google_sheets4::Result<()> {
+        use google_sheets4::api::*;
+
+        let sheet_config = self.config.google_sheet_config.as_ref().unwrap();
+        let header_rows = if sheet_config.summary_row { 2 } else { 1 };
+
+        let mut requests = Vec::new();
+        for (target, rows) in tables {
+            let tab_id = target.tab_id;
+            match self.sheets.rows.get(target) {
+                None => {
+                    // Rewrite the whole tab
+                    requests.push(Request {
+                        update_sheet_properties: Some(UpdateSheetPropertiesRequest {
+                            properties: Some(SheetProperties {
+                                sheet_id: Some(tab_id),
+                                grid_properties: Some(GridProperties {
+                                    frozen_row_count: Some(header_rows),
+                                    ..Default::default()
+                                }),
                                 ..Default::default()
                             }),
-                            ..Default::default()
-                        }),
-                        fields: Some("gridProperties.frozenRowCount".to_owned()),
-                    }),
-                    ..Default::default()
-                },
-                Request {
-                    repeat_cell: Some(RepeatCellRequest {
-                        fields: Some("*".to_owned()),
-                        range: Some(GridRange {
-                            ..Default::default()
+                            fields: Some("gridProperties.frozenRowCount".to_owned()),
                         }),
                         ..Default::default()
-                    }),
-                    ..Default::default()
-                },
-                Request {
-                    update_cells: Some(UpdateCellsRequest {
-                        rows: Some(rows),
-                        fields: Some("*".to_owned()),
-                        start: Some(GridCoordinate {
-                            row_index: Some(0),
-                            column_index: Some(0),
+                    });
+                    requests.push(Request {
+                        repeat_cell: Some(RepeatCellRequest {
+                            fields: Some("*".to_owned()),
+                            range: Some(GridRange {
+                                sheet_id: Some(tab_id),
+                                ..Default::default()
+                            }),
                             ..Default::default()
                         }),
                         ..Default::default()
-                    }),
-                    ..Default::default()
-                },
-            ]),
+                    });
+                    requests.push(self.update_rows_request(tab_id, 0, rows, "*"));
+                }
+                Some(old_rows) => {
+                    // Update changed rows
+                    for (index, (row, old_row)) in rows.iter().zip(old_rows).enumerate() {
+                        if row == old_row {
+                            continue;
+                        }
+                        let fields = if row.values == old_row.values {
+                            // Only the color has changed
+                            "userEnteredFormat"
+                        } else {
+                            "*"
+                        };
+                        requests.push(self.update_rows_request(
+                            tab_id,
+                            index,
+                            std::slice::from_ref(row),
+                            fields,
+                        ));
+                    }
+
+                    // Append new rows
+                    if rows.len() > old_rows.len() {
+                        requests.push(self.update_rows_request(
+                            tab_id,
+                            old_rows.len(),
+                            &rows[old_rows.len()..],
+                            "*",
+                        ));
+                    }
+
+                    // Clear removed rows
+                    if rows.len() < old_rows.len() {
+                        requests.push(Request {
+                            repeat_cell: Some(RepeatCellRequest {
+                                fields: Some("*".to_owned()),
+                                range: Some(GridRange {
+                                    sheet_id: Some(tab_id),
+                                    start_row_index: Some(rows.len() as i32),
+                                    end_row_index: Some(old_rows.len() as i32),
+                                    ..Default::default()
+                                }),
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        });
+                    }
+                }
+            }
+        }
+
+        if requests.is_empty() {
+            return Ok(());
+        }
+
+        let update_values = BatchUpdateSpreadsheetRequest {
+            requests: Some(requests),
             ..Default::default()
         };
         let result = self
@@ -131,24 +456,50 @@ impl GamejamBot {
             .as_ref()
             .unwrap()
             .spreadsheets()
-            .batch_update(
-                update_values,
-                &self.config.google_sheet_config.as_ref().unwrap().sheet_id,
-            )
+            .batch_update(update_values, spreadsheet_id)
             .add_scope(Scope::Spreadsheet)
             .doit()
             .await;
         result.map(|_| ())
     }
 
-    fn game_to_values(&self, game: &Submission) -> Vec<String> {
-        let mut authors = game.authors.iter();
-        let mut game_authors = authors.next().unwrap().to_owned();
-        for author in authors {
-            game_authors.push_str(", ");
-            game_authors.push_str(author);
+    fn update_rows_request(
+        &self,
+        tab_id: i32,
+        start_row: usize,
+        rows: &[SheetRow],
+        fields: &str,
+    ) -> google_sheets4::api::Request {
+        use google_sheets4::api::*;
+        let rows = rows
+            .iter()
+            .map(|row| {
+                let format = match row.game_type {
+                    Some(game_type) => self.game_to_format(game_type),
+                    None => Some(CellFormat {
+                        text_format: Some(TextFormat {
+                            bold: Some(true),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }),
+                };
+                self.values_to_row_data(row.values.clone(), format)
+            })
+            .collect();
+        Request {
+            update_cells: Some(UpdateCellsRequest {
+                rows: Some(rows),
+                fields: Some(fields.to_owned()),
+                start: Some(GridCoordinate {
+                    sheet_id: Some(tab_id),
+                    row_index: Some(start_row as i32),
+                    column_index: Some(0),
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
         }
-        vec![game.link.clone(), game_authors]
     }
 
     fn game_to_format(&self, game_type: GameType) -> Option<google_sheets4::api::CellFormat> {
@@ -159,14 +510,8 @@ impl GamejamBot {
             .as_ref()
             .unwrap()
             .cell_format;
-        let color = match game_type {
-            GameType::Queued => cell_format.color_queued.clone(),
-            GameType::Current => cell_format.color_current.clone(),
-            GameType::Skipped => cell_format.color_skipped.clone(),
-            GameType::Played => cell_format.color_played.clone(),
-        };
         Some(CellFormat {
-            background_color: color,
+            background_color: cell_format.color(game_type).cloned(),
             ..Default::default()
         })
     }
@@ -193,3 +538,153 @@ impl GamejamBot {
         }
     }
 }
+
+impl SheetColumn {
+    fn header(&self) -> &'static str {
+        match self {
+            SheetColumn::Name => "Game",
+            SheetColumn::Link => "Game link",
+            SheetColumn::Authors => "Authors",
+            SheetColumn::Luck => "Luck",
+            SheetColumn::SubmitTime => "Submitted",
+            SheetColumn::Lane => "Lane",
+            SheetColumn::Rating => "Rating",
+        }
+    }
+}
+
+/// Adds the games into the target's table, creating it if necessary
+fn add_games<'a>(
+    tables: &mut Vec<(SheetTarget, Vec<(&'a Submission, GameType)>)>,
+    target: SheetTarget,
+    games: impl IntoIterator<Item = (&'a Submission, GameType)>,
+) {
+    match tables.iter_mut().find(|(table, _)| *table == target) {
+        Some((_, table_games)) => table_games.extend(games),
+        None => tables.push((target, games.into_iter().collect())),
+    }
+}
+
+/// Formats unix time as `YYYY-MM-DD hh:mm` (UTC)
+fn format_time(time: u64) -> String {
+    let days = (time / 86400) as i64;
+    let seconds = time % 86400;
+
+    // Convert days since the epoch into the date (http://howardhinnant.github.io/date_algorithms.html)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
+
+/// Checks whether the request was rejected because of the rate limits
+fn is_quota_error(error: &google_sheets4::Error) -> bool {
+    match error {
+        google_sheets4::Error::Failure(response) => {
+            response.status() == hyper::StatusCode::TOO_MANY_REQUESTS
+        }
+        google_sheets4::Error::BadRequest(value) => {
+            value["error"]["code"] == 429 || value["error"]["status"] == "RESOURCE_EXHAUSTED"
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn idle() -> SheetsSync {
+        let mut sync = SheetsSync::new();
+        sync.succeeded(Vec::new());
+        sync
+    }
+
+    #[test]
+    fn changes_postpone_the_update() {
+        let mut sync = idle();
+        assert!(!sync.tick(10.0));
+
+        sync.queue_update(5.0);
+        assert!(!sync.tick(3.0));
+        sync.queue_update(5.0);
+        assert!(!sync.tick(3.0));
+        assert!(sync.tick(2.0));
+    }
+
+    #[test]
+    fn update_is_not_postponed_forever() {
+        let mut sync = idle();
+        let mut waited = 0.0;
+        loop {
+            sync.queue_update(5.0);
+            waited += 1.0;
+            if sync.tick(1.0) {
+                break;
+            }
+            assert!(waited < UPDATE_WAIT_MAX + 1.0);
+        }
+        assert_eq!(waited, UPDATE_WAIT_MAX);
+
+        // The limit starts over after the update
+        sync.succeeded(Vec::new());
+        sync.queue_update(5.0);
+        assert!(!sync.tick(4.0));
+        assert!(sync.tick(1.0));
+    }
+
+    #[test]
+    fn retries_back_off() {
+        let mut sync = idle();
+        assert_eq!(sync.failed(true), Some(RETRY_DELAY_MIN));
+        assert_eq!(sync.failed(true), Some(RETRY_DELAY_MIN * 2.0));
+        assert_eq!(sync.failed(true), Some(RETRY_DELAY_MIN * 4.0));
+
+        // Changes do not shorten the retry delay
+        sync.queue_update(1.0);
+        assert!(!sync.tick(RETRY_DELAY_MIN * 4.0 - 1.0));
+        assert!(sync.tick(1.0));
+
+        // But can postpone it further
+        sync.failed(true);
+        sync.queue_update(100.0);
+        assert!(!sync.tick(RETRY_DELAY_MIN * 8.0));
+        assert!(sync.tick(100.0));
+    }
+
+    #[test]
+    fn retry_delay_is_limited_and_reset() {
+        let mut sync = idle();
+        for _ in 0..20 {
+            sync.failed(true);
+        }
+        assert_eq!(sync.failed(true), Some(RETRY_DELAY_MAX));
+
+        sync.succeeded(Vec::new());
+        assert_eq!(sync.update_timer, None);
+        assert_eq!(sync.failed(true), Some(RETRY_DELAY_MIN));
+    }
+
+    #[test]
+    fn other_errors_are_not_retried() {
+        let mut sync = idle();
+        sync.queue_update(5.0);
+        assert_eq!(sync.failed(false), None);
+        assert!(!sync.tick(RETRY_DELAY_MAX));
+    }
+}
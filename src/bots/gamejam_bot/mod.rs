@@ -7,10 +7,12 @@ mod bot;
 mod bot_state;
 mod commands;
 mod config;
+mod export;
 mod google;
 
 use bot_state::*;
 use config::*;
+use export::*;
 use google::*;
 
 pub struct GamejamBot {
@@ -18,10 +20,13 @@ pub struct GamejamBot {
     cli: Option<Cli>,
     config: GamejamConfig,
     commands: Commands<Self>,
+    shared: Shared,
+    /// Whether the user registry has to be updated with the games and luck
+    users_changed: bool,
 
     // Google stuff
     hub: Option<Sheets>,
-    update_sheets_queued: bool,
+    sheets: SheetsSync,
 
     // Actual data
     state: GamejamState,
@@ -35,11 +40,14 @@ pub struct GamejamSerialized {
 
 impl GamejamBot {
     fn check_message(&mut self, message: &CommandMessage) -> Response {
+        let sender = message.sender.user();
+        self.update_author(&sender);
+
         // Check if waiting for reply
         let state = std::mem::take(&mut self.state.current_state);
         match state {
             GameJamState::Waiting { game, .. } => {
-                if game.authors.contains(&message.sender.name) {
+                if game.has_author(&sender) {
                     return self.set_current(Some(game));
                 }
             }
@@ -50,7 +58,7 @@ impl GamejamBot {
 
         // Try return if auto return is set
         if self.config.auto_return {
-            return self.return_game(&message.sender.name);
+            return self.return_game(&sender);
         }
 
         None
@@ -70,8 +78,138 @@ impl GamejamBot {
         }
     }
 
+    /// Updates the id and the name of the user in every game they are an author of
+    /// and in the blocklist
+    fn update_author(&mut self, user: &UserRef) {
+        if self.state.blocklist.update_user(user) {
+            save_into(&self.state.blocklist, BLOCKLIST_FILE).unwrap();
+        }
+        let mut changed = false;
+        let games = self
+            .state
+            .current_state
+            .current_mut()
+            .into_iter()
+            .chain(self.state.submissions.iter_mut());
+        for game in games {
+            for author in &mut game.authors {
+                changed |= author.update(user);
+            }
+        }
+        if changed {
+            self.save_all_games();
+        }
+    }
+
+    /// Resolves the user mentioned in a command against the authors and the user registry
+    fn find_user(&self, input: &str) -> UserRef {
+        let user = UserRef::parse(input);
+        let authors = self
+            .state
+            .current_state
+            .current()
+            .into_iter()
+            .chain(self.state.submissions.iter())
+            .flat_map(|game| &game.authors);
+        if let Some(author) = user.resolve(authors) {
+            return author.clone();
+        }
+        match self.shared.lock().unwrap().users.find(&user.name) {
+            Some(record) => record.user(),
+            None => user,
+        }
+    }
+
+    /// Luck is only relevant for the games that can take part in raffles
+    fn game_luck(&self, game: &Submission, game_type: GameType) -> Option<Luck> {
+        match game_type {
+            GameType::Queued | GameType::Skipped => Some(
+                self.state
+                    .raffle_weights
+                    .get(&game.link)
+                    .copied()
+                    .unwrap_or(self.config.raffle_default_weight),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Provides `{current_game}`, `{queue_length}` and `{queue_open}` for the other bots
+    fn update_variables(&self, variables: &mut Variables) {
+        let current_game = match self.state.current_state.current() {
+            Some(game) => game.to_string_link(false),
+            None => "nothing".to_owned(),
+        };
+        variables.set("current_game", current_game);
+        variables.set(
+            "queue_length",
+            self.state.submissions.queue.get_queue().count(),
+        );
+        variables.set("queue_open", self.state.is_queue_open);
+    }
+
+    /// Writes the submitted games and luck of every author into the user registry.
+    /// Authors from older versions, that are only known by name, are resolved here.
+    fn sync_users(&mut self, users: &mut UserRegistry) {
+        for user in users.users.values() {
+            self.update_author(&user.user());
+        }
+
+        let submissions = &self.state.submissions;
+        let unplayed = self
+            .state
+            .current_state
+            .current()
+            .into_iter()
+            .chain(submissions.queue.get_queue())
+            .chain(&submissions.skipped)
+            .chain(&submissions.pending)
+            .map(|game| (game, false));
+        let played = submissions.played_games.iter().map(|game| (game, true));
+
+        let mut games: HashMap<&str, (Vec<String>, Vec<String>)> = HashMap::new();
+        for (game, is_played) in unplayed.chain(played) {
+            for author in &game.authors {
+                let id = match &author.id {
+                    Some(id) => id,
+                    None => continue,
+                };
+                let (submitted, played) = games.entry(id).or_default();
+                submitted.push(game.link.clone());
+                if is_played {
+                    played.push(game.link.clone());
+                }
+            }
+        }
+
+        for user in users.iter_mut() {
+            let (submitted, played) = games.remove(user.id.as_str()).unwrap_or_default();
+            user.submitted_games = submitted;
+            user.played_games = played;
+            let user_ref = user.user();
+            user.luck = self
+                .state
+                .submissions
+                .find_game(|game| game.has_author(&user_ref))
+                .and_then(|(game, game_type)| self.game_luck(game, game_type));
+        }
+    }
+
+    fn save_all_games(&mut self) {
+        save_into(&self.state.submissions.played_games, PLAYED_GAMES_FILE).unwrap();
+        self.save_games().unwrap();
+    }
+
     fn save_games(&mut self) -> std::io::Result<()> {
-        self.update_sheets_queued = true;
+        self.users_changed = true;
+        self.queue_sheets_update();
+        if let Err(err) = self.export_queue() {
+            log(
+                &self.cli,
+                LogType::Error,
+                &format!("Failed to export the queue: {}", err),
+            );
+        }
         save_into(&self.state, SAVE_FILE)
     }
 }
@@ -1,13 +1,19 @@
 use super::*;
 
+mod announce_bot;
+mod counter_bot;
 mod custom_bot;
 mod gamejam_bot;
+mod mod_bot;
 mod quote_bot;
 mod timer_bot;
 mod vote_bot;
 
+pub use announce_bot::*;
+pub use counter_bot::*;
 pub use custom_bot::*;
 pub use gamejam_bot::*;
+pub use mod_bot::*;
 pub use quote_bot::*;
 pub use timer_bot::*;
 pub use vote_bot::*;
@@ -0,0 +1,51 @@
+use super::*;
+
+impl ModBot {
+    fn permit(&mut self, user: &UserRef) -> Response {
+        let user_name = &user.name;
+        // Known users are permitted by the id, the display name might not be the login
+        let user = match self.shared.lock().unwrap().users.find(user_name) {
+            Some(record) => record.user(),
+            None => user.clone(),
+        };
+        let until =
+            Instant::now() + std::time::Duration::from_secs_f32(self.config.permit_duration);
+        self.permits
+            .retain(|(permitted, _)| !permitted.matches(&user));
+        self.permits.push((user, until));
+        Some(
+            format!(
+                "@{}, you can post a link in the next {} seconds",
+                user_name, self.config.permit_duration
+            )
+            .into(),
+        )
+    }
+
+    fn rules_test(&self, text: &str) -> Response {
+        let violations = self.check_rules(text, 0);
+        if violations.is_empty() {
+            return Some("No rules are broken".into());
+        }
+        let violations = violations
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect::<Vec<_>>();
+        Some(format!("Broken rules: {}", violations.join(", ")).into())
+    }
+
+    pub fn commands() -> Commands<Self> {
+        Commands::new(vec![
+            TypedCommand::<Self>::new("!permit")
+                .user("user_name")
+                .finalize(AuthorityLevel::Moderator as _, |bot, _, args| {
+                    bot.permit(args.user(0)?)
+                }),
+            CommandBuilder::new().literal(["!modtest"]).line().finalize(
+                true,
+                AuthorityLevel::Server as _,
+                Arc::new(|bot, _, args| bot.rules_test(&args[0])),
+            ),
+        ])
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Instant;
+
+use super::*;
+
+mod commands;
+mod rules;
+
+use rules::*;
+
+const CONFIG_FILE: &str = "config/mod/mod_config.json";
+
+pub struct ModBot {
+    cli: Option<Cli>,
+    shared: Shared,
+    config: ModConfig,
+    commands: Commands<Self>,
+    rules: CompiledRules,
+    /// Recent messages of every user by the user id, used to detect repeated messages
+    history: HashMap<String, VecDeque<(String, Instant)>>,
+    /// Number of violations of every user by the user id and the time of the last one
+    strikes: HashMap<String, (usize, Instant)>,
+    /// Users allowed to post a link until the given time
+    permits: Vec<(UserRef, Instant)>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModSerialized {
+    config: ModConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ModConfig {
+    /// Users with this authority level or higher are never checked
+    exempt: AuthorityLevel,
+    links: Option<LinkRule>,
+    /// Case-insensitive regular expressions
+    banned_phrases: Vec<String>,
+    caps: Option<CapsRule>,
+    repeats: Option<RepeatRule>,
+    emotes: Option<EmoteRule>,
+    /// Actions taken on the first, second, etc. violation.
+    /// The last action is repeated for further violations.
+    actions: Vec<ModAction>,
+    /// Seconds after the last violation, when the violations are forgotten
+    strike_reset: f32,
+    /// Seconds, during which a permitted user can post a link
+    permit_duration: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LinkRule {
+    /// Links to these domains and their subdomains are allowed
+    allowed_domains: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CapsRule {
+    /// Shorter messages are not checked
+    min_letters: usize,
+    /// Maximum ratio of uppercase letters, from 0 to 1
+    max_ratio: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RepeatRule {
+    /// Maximum number of identical messages
+    max_repeats: usize,
+    /// Seconds, during which the messages are counted
+    period: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EmoteRule {
+    max_emotes: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+enum ModAction {
+    Warn,
+    Delete,
+    Timeout { seconds: u64 },
+    Ban,
+}
+
+impl Default for ModConfig {
+    fn default() -> Self {
+        Self {
+            exempt: AuthorityLevel::Vip,
+            links: Some(LinkRule {
+                allowed_domains: vec!["itch.io".to_owned(), "twitch.tv".to_owned()],
+            }),
+            banned_phrases: Vec::new(),
+            caps: Some(CapsRule {
+                min_letters: 15,
+                max_ratio: 0.7,
+            }),
+            repeats: Some(RepeatRule {
+                max_repeats: 3,
+                period: 60.0,
+            }),
+            emotes: Some(EmoteRule { max_emotes: 15 }),
+            actions: vec![
+                ModAction::Warn,
+                ModAction::Delete,
+                ModAction::Timeout { seconds: 60 },
+                ModAction::Timeout { seconds: 600 },
+            ],
+            strike_reset: 3600.0,
+            permit_duration: 60.0,
+        }
+    }
+}
+
+impl ModConfig {
+    fn save(&self) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(
+            std::io::BufWriter::new(std::fs::File::create(CONFIG_FILE)?),
+            self,
+        )?;
+        Ok(())
+    }
+
+    fn load() -> std::io::Result<Self> {
+        Ok(serde_json::from_reader(std::io::BufReader::new(
+            std::fs::File::open(CONFIG_FILE)?,
+        ))?)
+    }
+}
+
+impl ModBot {
+    pub fn new_boxed(cli: &Option<Cli>, shared: &Shared) -> Box<dyn Bot> {
+        let config = match ModConfig::load() {
+            Ok(config) => config,
+            Err(error) => match error.kind() {
+                std::io::ErrorKind::NotFound => {
+                    let config = ModConfig::default();
+                    config.save().unwrap();
+                    config
+                }
+                _ => panic!("{}", error),
+            },
+        };
+        let rules = CompiledRules::new(&config.banned_phrases).unwrap_or_else(|error| {
+            log(
+                cli,
+                LogType::Error,
+                &format!("Invalid banned phrase in {}: {}", CONFIG_FILE, error),
+            );
+            CompiledRules::new(&[]).unwrap()
+        });
+        Box::new(Self {
+            cli: cli.clone(),
+            shared: shared.clone(),
+            config,
+            commands: Self::commands(),
+            rules,
+            history: HashMap::new(),
+            strikes: HashMap::new(),
+            permits: Vec::new(),
+        })
+    }
+}
+
+impl BotPerformer for ModBot {
+    const NAME: &'static str = "ModBot";
+
+    fn commands(&self) -> &Commands<Self> {
+        &self.commands
+    }
+}
+
+#[async_trait]
+impl Bot for ModBot {
+    async fn handle_message(
+        &mut self,
+        shared: &Shared,
+        outbox: &Outbox,
+        channel: &ChannelLogin,
+        message: &CommandMessage,
+    ) {
+        if let MessageOrigin::Twitch = message.sender.origin {
+            if !message.sender.roles.satisfies(self.config.exempt) {
+                if let Some(violation) = self.moderate(message) {
+                    for command in self.punish(&message.sender, &violation) {
+                        outbox.send(channel.clone(), command);
+                    }
+                }
+            }
+        }
+        self.perform(&self.cli.clone(), shared, outbox, channel, message)
+            .await;
+    }
+
+    fn complete(
+        &self,
+        word: &str,
+        prompter: &Prompter,
+        start: usize,
+        end: usize,
+    ) -> Option<Vec<linefeed::Completion>> {
+        self.commands.complete(word, prompter, start, end)
+    }
+
+    fn help(&self) -> Vec<CommandInfo> {
+        command_infos(&self.commands)
+    }
+
+    fn serialize(&self) -> SerializedBot {
+        SerializedBot::Mod(ModSerialized {
+            config: self.config.clone(),
+        })
+    }
+}
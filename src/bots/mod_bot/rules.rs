@@ -0,0 +1,201 @@
+use super::*;
+
+pub struct CompiledRules {
+    link: regex::Regex,
+    banned_phrases: Vec<regex::Regex>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Violation {
+    Link { domain: String },
+    BannedPhrase,
+    Caps,
+    Repeat,
+    Emotes,
+}
+
+impl CompiledRules {
+    pub fn new(banned_phrases: &[String]) -> Result<Self, regex::Error> {
+        Ok(Self {
+            // Only links with a scheme or `www.` are detected,
+            // so that words like `node.js` or `file.rs` are not mistaken for links
+            link: regex::Regex::new(
+                r"(?i)\b(?:https?://(?:www\.)?|www\.)((?:[a-z0-9-]+\.)+[a-z]{2,})\b(?:[/?#:]\S*)?",
+            )?,
+            banned_phrases: banned_phrases
+                .iter()
+                .map(|phrase| {
+                    regex::RegexBuilder::new(phrase)
+                        .case_insensitive(true)
+                        .build()
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Link { domain } => write!(f, "links to {} are not allowed", domain),
+            Self::BannedPhrase => write!(f, "that phrase is not allowed"),
+            Self::Caps => write!(f, "please, don't shout"),
+            Self::Repeat => write!(f, "please, don't repeat yourself"),
+            Self::Emotes => write!(f, "please, use less emotes"),
+        }
+    }
+}
+
+impl ModBot {
+    /// Checks the message against the rules, that do not depend on the chat history
+    pub fn check_rules(&self, text: &str, emote_count: usize) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        if let Some(rule) = &self.config.links {
+            for captures in self.rules.link.captures_iter(text) {
+                let domain = captures[1].to_lowercase();
+                let allowed = rule.allowed_domains.iter().any(|allowed| {
+                    let allowed = allowed.to_lowercase();
+                    domain == allowed || domain.ends_with(&format!(".{}", allowed))
+                });
+                if !allowed {
+                    violations.push(Violation::Link { domain });
+                    break;
+                }
+            }
+        }
+
+        if self
+            .rules
+            .banned_phrases
+            .iter()
+            .any(|phrase| phrase.is_match(text))
+        {
+            violations.push(Violation::BannedPhrase);
+        }
+
+        if let Some(rule) = &self.config.caps {
+            let letters = text.chars().filter(|c| c.is_alphabetic()).count();
+            let uppercase = text.chars().filter(|c| c.is_uppercase()).count();
+            if letters >= rule.min_letters && uppercase as f32 > letters as f32 * rule.max_ratio {
+                violations.push(Violation::Caps);
+            }
+        }
+
+        if let Some(rule) = &self.config.emotes {
+            if emote_count > rule.max_emotes {
+                violations.push(Violation::Emotes);
+            }
+        }
+
+        violations
+    }
+
+    /// Checks the message from the chat and remembers it for the future checks
+    pub fn moderate(&mut self, message: &CommandMessage) -> Option<Violation> {
+        let user = user_key(&message.sender);
+        let text = message.message_text.trim();
+        let now = Instant::now();
+
+        let repeated = match &self.config.repeats {
+            Some(rule) => {
+                // Forget the old messages, and the users who have not written recently
+                self.history.retain(|_, history| {
+                    history.retain(|(_, time)| (now - *time).as_secs_f32() < rule.period);
+                    !history.is_empty()
+                });
+                let history = self.history.entry(user.clone()).or_default();
+                history.push_back((text.to_lowercase(), now));
+                let repeats = history
+                    .iter()
+                    .filter(|(previous, _)| *previous == text.to_lowercase())
+                    .count();
+                repeats > rule.max_repeats
+            }
+            None => false,
+        };
+
+        let mut violations = self.check_rules(text, message.sender.emote_count);
+        if repeated {
+            violations.push(Violation::Repeat);
+        }
+
+        // Permitted users can post one message with links
+        self.permits.retain(|(_, until)| now < *until);
+        let sender = message.sender.user();
+        let permitted = self
+            .permits
+            .iter()
+            .any(|(permitted, _)| permitted.matches(&sender));
+        if permitted
+            && violations
+                .iter()
+                .any(|violation| matches!(violation, Violation::Link { .. }))
+        {
+            self.permits
+                .retain(|(permitted, _)| !permitted.matches(&sender));
+            violations.retain(|violation| !matches!(violation, Violation::Link { .. }));
+        }
+
+        violations.into_iter().next()
+    }
+
+    /// Registers the violation and returns the commands to send to the chat
+    pub fn punish(&mut self, sender: &Sender, violation: &Violation) -> Vec<String> {
+        let user = user_key(sender);
+        let now = Instant::now();
+        let strike_reset = self.config.strike_reset;
+        // Forget the violations of the users, whose strikes have been reset
+        self.strikes
+            .retain(|_, (_, last)| (now - *last).as_secs_f32() <= strike_reset);
+        let (strikes, last) = self.strikes.entry(user).or_insert((0, now));
+        *strikes += 1;
+        *last = now;
+        let strikes = *strikes;
+
+        let action = match self.config.actions.get(strikes - 1) {
+            Some(&action) => action,
+            None => match self.config.actions.last() {
+                Some(&action) => action,
+                None => return Vec::new(),
+            },
+        };
+        log(
+            &self.cli,
+            LogType::Info,
+            &format!(
+                "{:?} {} for violation #{}: {}",
+                action, sender.login, strikes, violation
+            ),
+        );
+
+        match action {
+            ModAction::Warn => vec![format!(
+                "@{}, {}. This is a warning",
+                sender.name, violation
+            )],
+            ModAction::Delete => {
+                let mut commands = Vec::new();
+                if let Some(message_id) = &sender.message_id {
+                    commands.push(format!("/delete {}", message_id));
+                }
+                commands.push(format!("@{}, {}", sender.name, violation));
+                commands
+            }
+            // Display names can differ from the login, which the chat commands expect
+            ModAction::Timeout { seconds } => {
+                vec![format!(
+                    "/timeout {} {} {}",
+                    sender.login, seconds, violation
+                )]
+            }
+            ModAction::Ban => vec![format!("/ban {} {}", sender.login, violation)],
+        }
+    }
+}
+
+/// Users are remembered by the id, so that renaming does not reset their history.
+/// Only the console has no id.
+fn user_key(sender: &Sender) -> String {
+    sender.id.clone().unwrap_or_else(|| sender.login.clone())
+}
@@ -146,4 +146,8 @@ impl QuoteBot {
                 .split([random, new, remove, edit, rename, get])],
         }
     }
+
+    pub fn command_cooldowns() -> Vec<CommandCooldown> {
+        vec![CommandCooldown::new(["!quote"]).global(5.0).user(30.0)]
+    }
 }
@@ -21,7 +21,7 @@ pub struct QuoteSerialized {
 }
 
 impl QuoteBot {
-    pub fn new_boxed(cli: &Option<Cli>) -> Box<dyn Bot> {
+    pub fn new_boxed(cli: &Option<Cli>, _shared: &Shared) -> Box<dyn Bot> {
         let config = match QuoteConfig::load() {
             Ok(config) => config,
             Err(error) => match error.kind() {
@@ -62,17 +62,22 @@ impl BotPerformer for QuoteBot {
     fn commands(&self) -> &Commands<Self> {
         &self.commands
     }
+
+    fn cooldowns(&self) -> Vec<CommandCooldown> {
+        Self::command_cooldowns()
+    }
 }
 
 #[async_trait]
 impl Bot for QuoteBot {
     async fn handle_message(
         &mut self,
-        client: &TwitchClient,
+        shared: &Shared,
+        outbox: &Outbox,
         channel: &ChannelLogin,
         message: &CommandMessage,
     ) {
-        self.perform(&self.cli.clone(), client, channel, message)
+        self.perform(&self.cli.clone(), shared, outbox, channel, message)
             .await;
     }
 
@@ -86,6 +91,10 @@ impl Bot for QuoteBot {
         self.commands.complete(word, prompter, start, end)
     }
 
+    fn help(&self) -> Vec<CommandInfo> {
+        command_infos(&self.commands)
+    }
+
     fn serialize(&self) -> SerializedBot {
         SerializedBot::Quote(QuoteSerialized {
             config: self.config.clone(),
@@ -3,23 +3,6 @@ use std::sync::Arc;
 use super::*;
 
 impl TimerBot {
-    fn timer_from_str(&mut self, mode: &str, time: Option<&str>) -> Response {
-        let time = match time {
-            Some(time) => match Timer::parse_duration(time) {
-                Ok(time) => Some(time),
-                Err(err) => return Some(format!("Failed to parse time argument: {err}").into()),
-            },
-            None => None,
-        };
-
-        match mode {
-            "set" => self.timer_set(TimerMode::Idle, time),
-            "countup" => self.timer_set(TimerMode::Countup, time),
-            "countdown" => self.timer_set(TimerMode::Countdown, time),
-            _ => None,
-        }
-    }
-
     fn timer_pause(&mut self, paused: bool) -> Response {
         self.timer.paused = paused;
         match paused {
@@ -65,26 +48,22 @@ impl TimerBot {
                 }),
             );
 
-        let set_time = CommandBuilder::<Self>::new().word().finalize(
-            true,
-            AuthorityLevel::Moderator as _,
-            Arc::new(|bot, _, args| bot.timer_from_str(&args[0], Some(&args[1]))),
-        );
-
-        let set_no_time = CommandBuilder::<Self>::new().finalize(
-            true,
-            AuthorityLevel::Moderator as _,
-            Arc::new(|bot, _, args| bot.timer_from_str(&args[0], None)),
-        );
+        let set = [
+            ("set", TimerMode::Idle),
+            ("countup", TimerMode::Countup),
+            ("countdown", TimerMode::Countdown),
+        ]
+        .map(|(name, mode)| {
+            TypedCommand::<Self>::new(&format!("!timer {name}"))
+                .duration("hh:mm:ss")
+                .optional()
+                .finalize(AuthorityLevel::Moderator as _, move |bot, _, args| {
+                    bot.timer_set(mode, args.duration(0))
+                })
+        });
 
-        let set = CommandBuilder::new()
-            .choice(["set", "countup", "countdown"])
-            .split([set_time, set_no_time]);
-
-        Commands {
-            commands: vec![CommandBuilder::new()
-                .literal(["!timer"])
-                .split([pause, set])],
-        }
+        let mut commands = vec![CommandBuilder::new().literal(["!timer"]).split([pause])];
+        commands.extend(set);
+        Commands { commands }
     }
 }
@@ -17,17 +17,26 @@ pub struct TimerSerialized {
 }
 
 impl TimerBot {
-    pub fn new_boxed(cli: &Option<Cli>) -> Box<dyn Bot> {
+    pub fn new_boxed(cli: &Option<Cli>, shared: &Shared) -> Box<dyn Bot> {
+        let timer = Timer::from_status().unwrap_or_default();
+        // Provide `{timer}` for the custom commands
+        shared
+            .lock()
+            .unwrap()
+            .variables
+            .set("timer", timer.time_status());
         Box::new(Self {
             cli: cli.clone(),
             commands: Self::commands(),
-            timer: Timer::from_status().unwrap_or_default(),
+            timer,
         })
     }
 
-    fn update_timer(&mut self, delta_time: f32) {
+    fn update_timer(&mut self, shared: &Shared, delta_time: f32) {
         self.timer.update(delta_time);
-        self.update_status(&self.timer.time_status());
+        let status = self.timer.time_status();
+        self.update_status(&status);
+        shared.lock().unwrap().variables.set("timer", status);
     }
 }
 
@@ -43,16 +52,23 @@ impl BotPerformer for TimerBot {
 impl Bot for TimerBot {
     async fn handle_message(
         &mut self,
-        client: &TwitchClient,
+        shared: &Shared,
+        outbox: &Outbox,
         channel: &ChannelLogin,
         message: &CommandMessage,
     ) {
-        self.perform(&self.cli.clone(), client, channel, message)
+        self.perform(&self.cli.clone(), shared, outbox, channel, message)
             .await;
     }
 
-    async fn update(&mut self, _client: &TwitchClient, _channel: &String, delta_time: f32) {
-        self.update_timer(delta_time);
+    async fn update(
+        &mut self,
+        shared: &Shared,
+        _outbox: &Outbox,
+        _channel: &String,
+        delta_time: f32,
+    ) {
+        self.update_timer(shared, delta_time);
     }
 
     fn complete(
@@ -65,6 +81,10 @@ impl Bot for TimerBot {
         self.commands.complete(word, prompter, start, end)
     }
 
+    fn help(&self) -> Vec<CommandInfo> {
+        command_infos(&self.commands)
+    }
+
     fn serialize(&self) -> SerializedBot {
         SerializedBot::Timer(TimerSerialized {
             state: self.timer.clone(),
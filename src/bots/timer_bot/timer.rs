@@ -18,10 +18,9 @@ pub enum TimerMode {
 
 impl Timer {
     pub fn from_status() -> Result<Self, Box<dyn std::error::Error>> {
-        let time = Timer::parse_duration(&std::fs::read_to_string(format!(
-            "status/{}.txt",
-            TimerBot::NAME
-        ))?)?;
+        let time = parse_duration(
+            std::fs::read_to_string(format!("status/{}.txt", TimerBot::NAME))?.trim(),
+        )?;
         Ok(Self {
             time,
             paused: true,
@@ -48,16 +47,6 @@ impl Timer {
         Timer::format_duration(self.time)
     }
 
-    pub fn parse_duration(s: &str) -> Result<std::time::Duration, Box<dyn std::error::Error>> {
-        let mut secs = 0;
-        let mut multiplier = 1;
-        for t in s.split(':').rev() {
-            secs += t.parse::<u64>()? * multiplier;
-            multiplier *= 60;
-        }
-        Ok(std::time::Duration::from_secs(secs))
-    }
-
     pub fn format_duration(duration: std::time::Duration) -> String {
         let secs = duration.as_secs();
         let seconds = secs % 60;
@@ -1,60 +1,183 @@
 use super::*;
 
 impl VoteBot {
-    pub fn vote_start(&mut self) -> Response {
-        match &self.state.vote_mode {
-            VoteMode::Active { .. } => Some("The voting is in progress.".into()),
-            VoteMode::Inactive => {
-                self.state.vote_mode = VoteMode::Active {
-                    votes: HashMap::new(),
-                };
-                self.update_status("The voting is in progress");
-                Some("The voting has started. Type !vote <your vote>".into())
-            }
+    pub fn vote_start(&mut self, line: &str) -> Response {
+        if let VoteMode::Active { .. } = &self.state.vote_mode {
+            return Some("The voting is in progress.".into());
         }
+
+        // The first word is the time limit, if it is like `1:30` or `90s`,
+        // so that options like `1 | 2` are not taken for the time
+        let line = line.trim();
+        let (first, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let (time_left, rest) = match parse_time_limit(first) {
+            Some(Ok(seconds)) => (Some(seconds), rest),
+            Some(Err(error)) => return Some(error.into()),
+            None => (None, line),
+        };
+        let options = rest
+            .split('|')
+            .map(|option| option.trim().to_owned())
+            .filter(|option| !option.is_empty())
+            .collect::<Vec<_>>();
+        if options.len() == 1 {
+            return Some("Give at least two options separated by |".into());
+        }
+
+        let mut reply = if options.is_empty() {
+            "The voting has started. Type !vote <your vote>".to_owned()
+        } else {
+            let options = options
+                .iter()
+                .enumerate()
+                .map(|(index, option)| format!("{}. {}", index + 1, option))
+                .collect::<Vec<_>>();
+            format!(
+                "The voting has started: {}. Type !vote <number>",
+                options.join(", ")
+            )
+        };
+        if let Some(time_left) = time_left {
+            reply += &format!(". It finishes in {}", format_time(time_left));
+        }
+        self.state.vote_mode = VoteMode::Active {
+            votes: HashMap::new(),
+            options,
+            time_left,
+        };
+        self.warned_voters.clear();
+        self.update_tallies();
+        Some(reply.into())
     }
 
     pub fn vote_finish(&mut self) -> Response {
         let vote_mode = std::mem::replace(&mut self.state.vote_mode, VoteMode::Inactive);
         match vote_mode {
-            VoteMode::Active { votes } => {
+            VoteMode::Active { votes, options, .. } => {
                 let voters = votes.len();
-                let votes_count = {
-                    let mut votes_count = HashMap::new();
-                    for (_, vote) in votes {
-                        *votes_count.entry(vote).or_insert(0) += 1;
-                    }
-                    let mut votes_count: Vec<(String, usize)> = votes_count.into_iter().collect();
-                    votes_count.sort_by(|(vote_a, _), (vote_b, _)| vote_a.cmp(vote_b));
-                    votes_count
+                let results = tally(&votes, &options);
+                let lines = format_results(&results, voters);
+                self.update_status(&lines.join("\n"));
+                self.state.last_vote = results.clone();
+                if voters == 0 {
+                    return Some("The voting has finished with no votes.".into());
+                }
+
+                let top = results[0].1;
+                let winners = results
+                    .iter()
+                    .take_while(|(_, count)| *count == top)
+                    .map(|(option, _)| option.as_str())
+                    .collect::<Vec<_>>();
+                let winner = match winners.as_slice() {
+                    [winner] => format!(
+                        "The winner is {} with {} votes ({}%)",
+                        winner,
+                        top,
+                        percent(top, voters)
+                    ),
+                    _ => format!(
+                        "It's a tie between {} with {} votes each",
+                        winners.join(" and "),
+                        top
+                    ),
                 };
-                self.update_status(&serde_json::to_string(&votes_count).unwrap());
-                let response = format!(
-                    "The voting has finished with the total of {} votes and {} unique ones.",
-                    voters,
-                    votes_count.len(),
+                Some(
+                    format!(
+                        "The voting has finished with the total of {} votes. {}. Results: {}",
+                        voters,
+                        winner,
+                        lines.join(", ")
+                    )
+                    .into(),
                 )
-                .into();
-                self.state.last_vote = votes_count;
-                Some(response)
             }
             VoteMode::Inactive => Some("The voting should be started first: !vote start".into()),
         }
     }
 
     pub fn vote(&mut self, voter: String, vote: String) -> Response {
-        if let VoteMode::Active { votes } = &mut self.state.vote_mode {
-            votes.insert(voter, vote.to_lowercase());
+        // `!vote start` and `!vote finish` are matched as votes too
+        if matches!(vote.split_whitespace().next(), Some("start" | "finish")) {
+            return None;
+        }
+        if let VoteMode::Active { votes, options, .. } = &mut self.state.vote_mode {
+            let vote = vote.trim();
+            let vote = if options.is_empty() {
+                vote.to_lowercase()
+            } else {
+                // Options can be chosen by the number or by the name
+                let option = vote
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|number| options.get(number.checked_sub(1)?))
+                    .or_else(|| {
+                        options
+                            .iter()
+                            .find(|option| option.eq_ignore_ascii_case(vote))
+                    });
+                match option {
+                    Some(option) => option.clone(),
+                    None if self.warned_voters.insert(voter.clone()) => {
+                        return Some(
+                            ResponseMsg::from(format!(
+                                "Vote with a number from 1 to {}",
+                                options.len()
+                            ))
+                            .with_delivery(Delivery::Reply),
+                        )
+                    }
+                    None => return None,
+                }
+            };
+            votes.insert(voter, vote);
+            self.update_tallies();
         }
         None
     }
 
+    /// Writes the current votes into the status file
+    pub fn update_tallies(&self) {
+        if let VoteMode::Active {
+            votes,
+            options,
+            time_left,
+        } = &self.state.vote_mode
+        {
+            let mut lines = Vec::new();
+            if let Some(time_left) = time_left {
+                lines.push(format!("Time left: {}", format_time(*time_left)));
+            }
+            if options.is_empty() {
+                lines.extend(format_results(&tally(votes, options), votes.len()));
+            } else {
+                // Keep the order of the options, so that the numbers match
+                let results = tally(votes, options);
+                for (index, option) in options.iter().enumerate() {
+                    let count = results
+                        .iter()
+                        .find(|(result, _)| result == option)
+                        .map_or(0, |(_, count)| *count);
+                    lines.push(format!(
+                        "{}. {}: {} ({}%)",
+                        index + 1,
+                        option,
+                        count,
+                        percent(count, votes.len())
+                    ));
+                }
+            }
+            self.update_status(&lines.join("\n"));
+        }
+    }
+
     pub fn commands() -> Commands<Self> {
-        let start = CommandBuilder::<Self>::new().literal(["start"]).finalize(
-            true,
-            AuthorityLevel::Broadcaster as _,
-            Arc::new(|bot, _, _| bot.vote_start()),
-        );
+        let start = TypedCommand::<Self>::new("!vote start")
+            .line("time option1 | option2")
+            .optional()
+            .finalize(AuthorityLevel::Broadcaster as _, |bot, _, args| {
+                bot.vote_start(args.text(0).unwrap_or(""))
+            });
 
         let finish = CommandBuilder::<Self>::new().literal(["finish"]).finalize(
             true,
@@ -64,14 +187,84 @@ impl VoteBot {
 
         let vote = CommandBuilder::<Self>::new().line().finalize(
             true,
-            AuthorityLevel::Broadcaster as _,
+            AuthorityLevel::Viewer as _,
             Arc::new(|bot, sender, args| bot.vote(sender.name.to_owned(), args[0].to_owned())),
         );
 
         Commands {
-            commands: vec![CommandBuilder::new()
-                .literal(["!vote"])
-                .split([start, finish, vote])],
+            commands: vec![
+                start,
+                CommandBuilder::new()
+                    .literal(["!vote"])
+                    .split([finish, vote]),
+            ],
+        }
+    }
+}
+
+/// Counts the votes, sorted by the count.
+/// Predefined options are included even without votes.
+fn tally(votes: &HashMap<String, String>, options: &[String]) -> Vec<(String, usize)> {
+    let mut results = options
+        .iter()
+        .map(|option| (option.clone(), 0))
+        .collect::<Vec<_>>();
+    for vote in votes.values() {
+        match results.iter_mut().find(|(option, _)| option == vote) {
+            Some((_, count)) => *count += 1,
+            None => results.push((vote.clone(), 1)),
         }
     }
+    if options.is_empty() {
+        results.sort_by(|(vote_a, _), (vote_b, _)| vote_a.cmp(vote_b));
+    }
+    // The sort is stable, so equal counts keep the order of the options
+    results.sort_by(|(_, count_a), (_, count_b)| count_b.cmp(count_a));
+    results
+}
+
+fn format_results(results: &[(String, usize)], voters: usize) -> Vec<String> {
+    results
+        .iter()
+        .map(|(option, count)| format!("{}: {} ({}%)", option, count, percent(*count, voters)))
+        .collect()
+}
+
+fn percent(count: usize, total: usize) -> u32 {
+    if total == 0 {
+        0
+    } else {
+        (count as f32 / total as f32 * 100.0).round() as u32
+    }
+}
+
+/// Parses the time limit of the voting in the `mm:ss` or `hh:mm:ss` format,
+/// or with a unit, like `90s`, `5m` or `1h`.
+/// Returns None if the word is not a time limit.
+fn parse_time_limit(word: &str) -> Option<Result<f32, String>> {
+    if word.contains(':') {
+        return Some(parse_duration(word).map(|duration| duration.as_secs_f32()));
+    }
+    let unit = match word.chars().last()? {
+        's' => 1,
+        'm' => 60,
+        'h' => 3600,
+        _ => return None,
+    };
+    let number = &word[..word.len() - 1];
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let seconds = number
+        .parse::<u64>()
+        .ok()
+        .and_then(|number| number.checked_mul(unit))
+        .ok_or_else(|| format!("{word} is too long"));
+    Some(seconds.map(|seconds| seconds as f32))
+}
+
+/// Formats the seconds like `mm:ss`
+fn format_time(seconds: f32) -> String {
+    let seconds = seconds.ceil().max(0.0) as u64;
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
 }
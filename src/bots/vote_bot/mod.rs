@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use super::*;
 
@@ -8,6 +8,8 @@ pub struct VoteBot {
     cli: Option<Cli>,
     commands: Commands<Self>,
     state: VoteState,
+    /// Voters that have been told how to vote in the current voting, they are answered only once
+    warned_voters: HashSet<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -24,11 +26,18 @@ pub struct VoteSerialized {
 #[derive(Clone, Debug, Serialize)]
 enum VoteMode {
     Inactive,
-    Active { votes: HashMap<String, String> },
+    Active {
+        /// Vote of each voter, one of the options if they are given
+        votes: HashMap<String, String>,
+        /// Options to vote for, any vote is accepted if empty
+        options: Vec<String>,
+        /// Seconds until the voting finishes automatically
+        time_left: Option<f32>,
+    },
 }
 
 impl VoteBot {
-    pub fn new_boxed(cli: &Option<Cli>) -> Box<dyn Bot> {
+    pub fn new_boxed(cli: &Option<Cli>, _shared: &Shared) -> Box<dyn Bot> {
         Box::new(Self {
             cli: cli.clone(),
             commands: Self::commands(),
@@ -36,10 +45,33 @@ impl VoteBot {
                 vote_mode: VoteMode::Inactive,
                 last_vote: Vec::new(),
             },
+            warned_voters: HashSet::new(),
         })
     }
 }
 
+impl VoteBot {
+    /// Finishes the voting when the time is up
+    fn update(&mut self, delta_time: f32) -> Response {
+        let time_left = match &mut self.state.vote_mode {
+            VoteMode::Active {
+                time_left: Some(time_left),
+                ..
+            } => time_left,
+            _ => return None,
+        };
+        let seconds = time_left.ceil();
+        *time_left -= delta_time;
+        if *time_left <= 0.0 {
+            return self.vote_finish();
+        }
+        if time_left.ceil() < seconds {
+            self.update_tallies();
+        }
+        None
+    }
+}
+
 impl BotPerformer for VoteBot {
     const NAME: &'static str = "VoteBot";
 
@@ -52,14 +84,27 @@ impl BotPerformer for VoteBot {
 impl Bot for VoteBot {
     async fn handle_message(
         &mut self,
-        client: &TwitchClient,
+        shared: &Shared,
+        outbox: &Outbox,
         channel: &ChannelLogin,
         message: &CommandMessage,
     ) {
-        self.perform(&self.cli.clone(), client, channel, message)
+        self.perform(&self.cli.clone(), shared, outbox, channel, message)
             .await;
     }
 
+    async fn update(
+        &mut self,
+        _shared: &Shared,
+        outbox: &Outbox,
+        channel: &ChannelLogin,
+        delta_time: f32,
+    ) {
+        if let Some(reply) = self.update(delta_time) {
+            outbox.send(channel.clone(), reply.message);
+        }
+    }
+
     fn complete(
         &self,
         word: &str,
@@ -70,6 +115,10 @@ impl Bot for VoteBot {
         self.commands.complete(word, prompter, start, end)
     }
 
+    fn help(&self) -> Vec<CommandInfo> {
+        command_infos(&self.commands)
+    }
+
     fn serialize(&self) -> SerializedBot {
         SerializedBot::Vote(VoteSerialized {
             state: self.state.clone(),
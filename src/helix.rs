@@ -0,0 +1,119 @@
+use google_sheets4::{hyper, hyper_rustls};
+use std::collections::HashMap;
+
+use super::*;
+
+const HELIX_URL: &str = "https://api.twitch.tv/helix";
+
+type HttpsClient = hyper::Client<hyper_rustls::HttpsConnector<hyper::client::HttpConnector>>;
+
+/// Client of the twitch api, used for the messages that can not be sent through the chat:
+/// whispers and announcements.
+/// The token needs the `user:manage:whispers` and `moderator:manage:announcements` scopes.
+#[derive(Clone)]
+pub struct Helix {
+    client: HttpsClient,
+    client_id: String,
+    oauth_token: String,
+    /// Login of the bot account
+    login_name: String,
+    /// User ids by the login, so that they are requested only once
+    user_ids: HashMap<String, String>,
+}
+
+impl Helix {
+    pub fn new(client_id: String, oauth_token: String, login_name: String) -> Self {
+        let connector = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .https_only()
+            .enable_http1()
+            .build();
+        Self {
+            client: hyper::Client::builder().build(connector),
+            client_id,
+            oauth_token,
+            login_name: login_name.to_lowercase(),
+            user_ids: HashMap::new(),
+        }
+    }
+
+    /// Sends a whisper from the bot account to the user
+    pub async fn whisper(&mut self, user_id: &str, message: &str) -> Result<(), String> {
+        let bot_id = self.user_id(&self.login_name.clone()).await?;
+        self.request(
+            hyper::Method::POST,
+            &format!("/whispers?from_user_id={bot_id}&to_user_id={user_id}"),
+            Some(serde_json::json!({ "message": message })),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Sends an announcement into the channel, the bot has to be a moderator there
+    pub async fn announce(&mut self, channel: &str, message: &str) -> Result<(), String> {
+        let bot_id = self.user_id(&self.login_name.clone()).await?;
+        let broadcaster_id = self.user_id(channel).await?;
+        self.request(
+            hyper::Method::POST,
+            &format!("/chat/announcements?broadcaster_id={broadcaster_id}&moderator_id={bot_id}"),
+            Some(serde_json::json!({ "message": message })),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn user_id(&mut self, login: &str) -> Result<String, String> {
+        if let Some(id) = self.user_ids.get(login) {
+            return Ok(id.clone());
+        }
+        let response = self
+            .request(hyper::Method::GET, &format!("/users?login={login}"), None)
+            .await?;
+        let id = response["data"][0]["id"]
+            .as_str()
+            .ok_or_else(|| format!("Unknown twitch user {login}"))?
+            .to_owned();
+        self.user_ids.insert(login.to_owned(), id.clone());
+        Ok(id)
+    }
+
+    async fn request(
+        &self,
+        method: hyper::Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value, String> {
+        let body = match body {
+            Some(body) => hyper::Body::from(body.to_string()),
+            None => hyper::Body::empty(),
+        };
+        let request = hyper::Request::builder()
+            .method(method)
+            .uri(format!("{HELIX_URL}{path}"))
+            .header("Client-Id", &self.client_id)
+            .header("Authorization", format!("Bearer {}", self.oauth_token))
+            .header("Content-Type", "application/json")
+            .body(body)
+            .map_err(|error| error.to_string())?;
+        let response = self
+            .client
+            .request(request)
+            .await
+            .map_err(|error| error.to_string())?;
+        let status = response.status();
+        let bytes = hyper::body::to_bytes(response.into_body())
+            .await
+            .map_err(|error| error.to_string())?;
+        if !status.is_success() {
+            return Err(format!(
+                "Twitch responded with {status}: {}",
+                String::from_utf8_lossy(&bytes)
+            ));
+        }
+        // Whispers and announcements respond with no content
+        if bytes.is_empty() {
+            return Ok(serde_json::Value::Null);
+        }
+        serde_json::from_slice(&bytes).map_err(|error| error.to_string())
+    }
+}
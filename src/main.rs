@@ -9,11 +9,17 @@ use twitch_irc::{login::StaticLoginCredentials, ClientConfig};
 use twitch_bot::prelude::*;
 
 mod bots;
+mod helix;
 mod main_bot;
+mod outbox;
 mod server;
+mod shared;
 mod traits;
 
+use helix::*;
 use main_bot::*;
+use outbox::*;
+use shared::*;
 use traits::*;
 
 pub type BotName = String;
@@ -49,14 +55,22 @@ async fn main() {
         login_config.login_name.clone(),
         Some(login_config.oauth_token.clone()),
     ));
+    let helix = login_config.client_id.clone().map(|client_id| {
+        Helix::new(
+            client_id,
+            login_config.oauth_token.clone(),
+            login_config.login_name.clone(),
+        )
+    });
     let channel_login = login_config.channel_login;
 
     // Connect to Twitch
     let (mut incoming_messages, client) = async { TwitchClient::new(client_config) }.compat().await;
 
-    let (main_bot, console_handle, console_abort) = if args.no_cli {
+    let (main_bot, outbox, console_handle, console_abort) = if args.no_cli {
         let main_bot = Arc::new(MutexBot::new(MainBot::new(None, active_bots)));
-        (main_bot, None, None)
+        let outbox = Outbox::new(None, client.clone(), helix);
+        (main_bot, outbox, None, None)
     } else {
         // Setup CLI
         let cli = Arc::new(linefeed::Interface::new("nertsal-bot").unwrap());
@@ -64,10 +78,11 @@ async fn main() {
         let main_bot = Arc::new(MutexBot::new(main_bot));
         let completer = main_bot.clone();
         cli.set_completer(completer);
+        let outbox = Outbox::new(Some(cli.clone()), client.clone(), helix);
 
         // Initialize CLI handle
         let bot = Arc::clone(&main_bot);
-        let client_clone = client.clone();
+        let outbox_clone = outbox.clone();
         let channel_login_clone = channel_login.clone();
         let (console_handle, console_abort) =
             futures::future::abortable(tokio::spawn(async move {
@@ -77,12 +92,17 @@ async fn main() {
                     let mut bot_lock = bot.lock().await;
                     bot_lock
                         .handle_message(
-                            &client_clone,
+                            &outbox_clone,
                             &channel_login_clone,
                             &CommandMessage {
                                 sender: Sender {
                                     name: "Server".to_owned(),
+                                    id: None,
+                                    login: "server".to_owned(),
                                     origin: MessageOrigin::Console,
+                                    roles: UserRoles::with_level(AuthorityLevel::Server),
+                                    message_id: None,
+                                    emote_count: 0,
                                 },
                                 message_text: input.clone(),
                                 authority_level: AuthorityLevel::Server as usize,
@@ -97,16 +117,16 @@ async fn main() {
                 bot.lock().await.queue_shutdown = true;
             }));
 
-        (main_bot, Some(console_handle), Some(console_abort))
+        (main_bot, outbox, Some(console_handle), Some(console_abort))
     };
 
     // Initialize twitch handle
     let bot = Arc::clone(&main_bot);
-    let client_clone = client.clone();
+    let outbox_clone = outbox.clone();
     let (message_handle, message_abort) = futures::future::abortable(tokio::spawn(async move {
         while let Some(message) = incoming_messages.next().await {
             let mut bot_lock = bot.lock().await;
-            bot_lock.handle_server_message(&client_clone, message).await;
+            bot_lock.handle_server_message(&outbox_clone, message).await;
         }
         let mut bot_lock = bot.lock().await;
         bot_lock.log(LogType::Info, "Chat handle shut down");
@@ -135,7 +155,7 @@ async fn main() {
 
         let result = rocket::custom(config)
             .manage(Arc::clone(&bot))
-            .mount("/", routes![index, get_state, events])
+            .mount("/", routes![index, get_state, events, queue, users])
             .launch()
             .await;
 
@@ -152,7 +172,6 @@ async fn main() {
 
     // Initialize update handle
     let bot = Arc::clone(&main_bot);
-    let client_clone = client.clone();
     let channel_login_clone = channel_login.clone();
     let update_handle = tokio::spawn(async move {
         const FIXED_DELTA_TIME: f32 = 1.0;
@@ -162,7 +181,7 @@ async fn main() {
             interval.tick().await;
             let mut bot_lock = bot.lock().await;
             bot_lock
-                .update(&client_clone, &channel_login_clone, FIXED_DELTA_TIME)
+                .update(&outbox, &channel_login_clone, FIXED_DELTA_TIME)
                 .await;
 
             if bot_lock.queue_shutdown {
@@ -200,6 +219,10 @@ pub struct LoginConfig {
     pub login_name: String,
     pub oauth_token: String,
     pub channel_login: String,
+    /// Client id of the application, that the token was issued to.
+    /// Required for whispers and announcements, otherwise they are sent as usual messages.
+    #[serde(default)]
+    pub client_id: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
@@ -220,14 +243,12 @@ pub fn log(cli: &Option<Cli>, log_type: LogType, message: &str) {
     }
 }
 
-pub async fn send_message(
-    cli: &Option<Cli>,
-    client: &TwitchClient,
-    channel: String,
-    message: String,
-) {
-    log(cli, LogType::Send, &format!("{}: {}", channel, message));
-    client.say(channel, message).await.unwrap();
+/// Seconds since the unix epoch
+pub fn unix_time() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or_default()
 }
 
 impl Display for LogType {
@@ -5,33 +5,57 @@ pub struct MainBot {
     pub(super) commands: Commands<MainBot>,
     pub(super) bots: Bots,
     pub queue_shutdown: bool,
-    users: HashMap<String, User>,
+    pub(super) roles: CustomRoles,
+    pub(super) shared: Shared,
+    /// Channel of the message being handled, used to apply the permission overrides
+    pub(super) channel: ChannelLogin,
+    name_colors: NameColors,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct User {
-    color: Option<Color>,
-}
-
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-struct Color {
-    r: u8,
-    g: u8,
-    b: u8,
+/// Colors of the users' names, used to color the names in the chat log
+#[derive(Default)]
+struct NameColors {
+    /// Colors by the lowercase name
+    colors: HashMap<String, Color>,
+    /// Matches any of the names, longer names first
+    regex: Option<regex::Regex>,
+    built: bool,
 }
 
 impl MainBot {
     pub fn new(cli: Option<&Cli>, active_bots: ActiveBots) -> Self {
-        Self {
+        let shared = Arc::new(std::sync::Mutex::new(
+            SharedState::load().expect("Failed to load shared state"),
+        ));
+        let mut roles = CustomRoles::load().expect("Failed to load config/roles.json");
+        if let Some(unknown) = roles.migrate(&shared.lock().unwrap().users) {
+            if !unknown.is_empty() {
+                log(
+                    &cli.cloned(),
+                    LogType::Warn,
+                    &format!(
+                        "Dropped the custom roles of unknown users, grant them again: {}",
+                        unknown.join(", ")
+                    ),
+                );
+            }
+            roles.save().expect("Failed to save roles");
+        }
+        let mut bot = Self {
             cli: cli.cloned(),
             commands: Self::commands(active_bots.iter().cloned()),
-            bots: Bots::new(&cli.cloned(), active_bots),
+            bots: Bots::new(&cli.cloned(), &shared, active_bots),
             queue_shutdown: false,
-            users: HashMap::new(),
-        }
+            roles,
+            shared,
+            channel: ChannelLogin::new(),
+            name_colors: NameColors::default(),
+        };
+        bot.refresh_command_names();
+        bot
     }
 
-    pub async fn handle_server_message(&mut self, client: &TwitchClient, message: ServerMessage) {
+    pub async fn handle_server_message(&mut self, outbox: &Outbox, message: ServerMessage) {
         match message {
             ServerMessage::Join(message) => {
                 log(
@@ -46,16 +70,30 @@ impl MainBot {
                 }
             }
             ServerMessage::Privmsg(message) => {
-                self.log_chat_message(&message);
-                self.handle_message(
-                    client,
-                    &message.channel_login,
-                    &private_to_command_message(&message),
-                )
-                .await;
+                let mut command_message = private_to_command_message(&message);
+                self.roles.apply(&mut command_message);
+                self.log_chat_message(&message, &command_message.sender.roles);
+                if let Some(event) = ChatEvent::from_privmsg(&message) {
+                    self.handle_event(outbox, &message.channel_login, &event)
+                        .await;
+                }
+                self.handle_message(outbox, &message.channel_login, &command_message)
+                    .await;
+            }
+            ServerMessage::UserState(message) => {
+                outbox.set_moderator(
+                    message
+                        .badges
+                        .iter()
+                        .any(|badge| badge.name == "moderator" || badge.name == "broadcaster"),
+                );
             }
             ServerMessage::UserNotice(message) => {
                 self.log(LogType::Event, &message.system_message);
+                if let Some(event) = ChatEvent::from_user_notice(&message) {
+                    self.handle_event(outbox, &message.channel_login, &event)
+                        .await;
+                }
             }
             _ => (),
         }
@@ -74,29 +112,76 @@ impl MainBot {
 
     pub async fn handle_message(
         &mut self,
-        client: &TwitchClient,
+        outbox: &Outbox,
         channel: &ChannelLogin,
         message: &CommandMessage,
     ) {
-        self.perform(&self.cli.clone(), client, channel, message)
+        self.channel = channel.clone();
+        self.refresh_command_names();
+        let shared = self.shared.clone();
+        self.perform(&self.cli.clone(), &shared, outbox, channel, message)
             .await;
+        // The message could have enabled or disabled some bots
+        self.refresh_command_names();
 
         for bot in self.bots.active.values_mut() {
-            bot.handle_message(client, channel, message).await;
+            bot.handle_message(&shared, outbox, channel, message).await;
+        }
+    }
+
+    /// Collects the names of the commands again, if some bot has added or removed commands
+    fn refresh_command_names(&mut self) {
+        if !self.shared.lock().unwrap().command_names_outdated {
+            return;
+        }
+        let command_names = self
+            .all_commands()
+            .into_iter()
+            .flat_map(|(_, info)| {
+                info.name_alternatives()
+                    .map(|name| name.to_owned())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let mut shared = self.shared.lock().unwrap();
+        shared.command_names = command_names;
+        shared.command_names_outdated = false;
+    }
+
+    pub async fn handle_event(
+        &mut self,
+        outbox: &Outbox,
+        channel: &ChannelLogin,
+        event: &ChatEvent,
+    ) {
+        for bot in self.bots.active.values_mut() {
+            bot.handle_event(&self.shared, outbox, channel, event).await;
         }
     }
 
-    pub async fn update(&mut self, client: &TwitchClient, channel: &ChannelLogin, delta_time: f32) {
+    pub async fn update(&mut self, outbox: &Outbox, channel: &ChannelLogin, delta_time: f32) {
+        if let Err(err) = self.shared.lock().unwrap().users.update(delta_time) {
+            self.log(LogType::Error, &format!("Failed to save users: {err}"));
+        }
         for bot in self.bots.active.values_mut() {
-            bot.update(client, channel, delta_time).await;
+            bot.update(&self.shared, outbox, channel, delta_time).await;
         }
     }
 
+    pub fn users(&self) -> Vec<UserRecord> {
+        let shared = self.shared.lock().unwrap();
+        shared.users.users.values().cloned().collect()
+    }
+
     pub fn serialize(&self) -> impl Iterator<Item = SerializedBot> + '_ {
         self.bots.active.values().map(|bot| bot.serialize())
     }
 
-    fn log_chat_message(&mut self, message: &twitch_irc::message::PrivmsgMessage) {
+    fn log_chat_message(
+        &mut self,
+        message: &twitch_irc::message::PrivmsgMessage,
+        roles: &UserRoles,
+    ) {
         use colored::Colorize;
 
         // Color the user's name
@@ -116,10 +201,12 @@ impl MainBot {
             None => (message.sender.name.clone(), None),
         };
         // Register the user
-        self.users
-            .entry(message.sender.name.to_lowercase())
-            .or_insert(User { color: None })
-            .color = color;
+        {
+            let mut shared = self.shared.lock().unwrap();
+            shared.users.record_message(&message.sender, color, roles);
+            self.name_colors
+                .update(&shared.users, &message.sender.name, color);
+        }
 
         // Print the colored message
         if let Some(cli) = &self.cli {
@@ -143,45 +230,27 @@ impl MainBot {
             Colored(colored::ColoredString),
         }
 
-        let mut result = vec![Slice::Raw(message)];
-        let mut users: Vec<_> = self
-            .users
-            .iter()
-            .filter_map(|(name, user)| user.color.map(|color| (name, color)))
-            .collect();
-        users.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
-        for (user, color) in users {
-            let regex = regex::RegexBuilder::new(&regex::escape(user))
-                .case_insensitive(true)
-                .build()
-                .unwrap();
-            result = result
-                .into_iter()
-                .flat_map(|slice| match slice {
-                    Slice::Raw(slice) => {
-                        let mut last_match = 0;
-                        let mut res: Vec<_> = regex
-                            .find_iter(slice)
-                            .flat_map(|mat| {
-                                let mut res = Vec::with_capacity(3);
-                                if mat.start() > 0 {
-                                    res.push(Slice::Raw(&slice[last_match..mat.start()]));
-                                }
-                                res.push(Slice::Colored(
-                                    mat.as_str().truecolor(color.r, color.g, color.b),
-                                ));
-                                last_match = mat.end();
-                                res
-                            })
-                            .collect();
-                        if last_match < slice.len() {
-                            res.push(Slice::Raw(&slice[last_match..]));
-                        }
-                        res
-                    }
-                    colored => vec![colored],
-                })
-                .collect();
+        let regex = match &self.name_colors.regex {
+            Some(regex) => regex,
+            None => return vec![message.clear()],
+        };
+        let mut result = Vec::new();
+        let mut last_match = 0;
+        for mat in regex.find_iter(message) {
+            let color = match self.name_colors.colors.get(&mat.as_str().to_lowercase()) {
+                Some(color) => color,
+                None => continue,
+            };
+            if mat.start() > last_match {
+                result.push(Slice::Raw(&message[last_match..mat.start()]));
+            }
+            result.push(Slice::Colored(
+                mat.as_str().truecolor(color.r, color.g, color.b),
+            ));
+            last_match = mat.end();
+        }
+        if last_match < message.len() {
+            result.push(Slice::Raw(&message[last_match..]));
         }
 
         result
@@ -212,3 +281,33 @@ impl BotPerformer for MainBot {
         &self.commands
     }
 }
+
+impl NameColors {
+    /// Rebuilds the regex, if the color of the user is not known yet
+    fn update(&mut self, users: &UserRegistry, name: &str, color: Option<Color>) {
+        if self.built && self.colors.get(&name.to_lowercase()).copied() == color {
+            return;
+        }
+        self.built = true;
+        self.colors = users
+            .users
+            .values()
+            .filter_map(|user| Some((user.name.to_lowercase(), user.color?)))
+            .collect();
+        let mut names = self.colors.keys().collect::<Vec<_>>();
+        names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+        let pattern = names
+            .into_iter()
+            .map(|name| regex::escape(name))
+            .collect::<Vec<_>>()
+            .join("|");
+        self.regex = if self.colors.is_empty() {
+            None
+        } else {
+            regex::RegexBuilder::new(&pattern)
+                .case_insensitive(true)
+                .build()
+                .ok()
+        };
+    }
+}
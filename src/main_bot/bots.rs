@@ -6,13 +6,13 @@ pub(super) struct Bots {
 }
 
 impl Bots {
-    pub fn new(cli: &Option<Cli>, active_bots: ActiveBots) -> Self {
+    pub fn new(cli: &Option<Cli>, shared: &Shared, active_bots: ActiveBots) -> Self {
         let constructors = constructors().into_iter().collect::<HashMap<_, _>>();
         let mut active = HashMap::new();
         for bot_name in active_bots {
             match constructors.get(&bot_name) {
                 Some(constructor) => {
-                    let bot = constructor(cli);
+                    let bot = constructor(cli, shared);
                     log(cli, LogType::Info, &format!("Spawned {bot_name}"));
                     active.insert(bot_name, bot);
                 }
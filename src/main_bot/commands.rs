@@ -1,5 +1,9 @@
+use std::collections::BTreeSet;
+
 use super::*;
 
+const COMMANDS_EXPORT_FILE: &str = "status/commands.md";
+
 impl MainBot {
     fn enable(&mut self, bot_name: &str) -> Response {
         if bot_name == "all" {
@@ -14,9 +18,10 @@ impl MainBot {
                 res += "Everyone is already active";
             } else {
                 for (bot_name, constructor) in disabled {
-                    let bot = constructor(&self.cli);
+                    let bot = constructor(&self.cli, &self.shared);
                     self.bots.active.insert(bot_name.to_owned(), bot);
                     self.save_bots().expect("Failed to save state");
+                    self.shared.lock().unwrap().command_names_outdated = true;
                     res += &format!("{bot_name}, ");
                 }
                 res.pop();
@@ -31,9 +36,10 @@ impl MainBot {
         }
         match self.bots.constructors.get(bot_name) {
             Some(constructor) => {
-                let bot = constructor(&self.cli);
+                let bot = constructor(&self.cli, &self.shared);
                 self.bots.active.insert(bot_name.to_owned(), bot);
                 self.save_bots().expect("Failed to save state");
+                self.shared.lock().unwrap().command_names_outdated = true;
                 Some(format!("{bot_name} is now active").into())
             }
             None => Some(format!("I don't know about {bot_name}").into()),
@@ -52,6 +58,7 @@ impl MainBot {
             if active.is_empty() {
                 res += "Everyone is already resting";
             } else {
+                self.shared.lock().unwrap().command_names_outdated = true;
                 for bot_name in active {
                     res += &format!("{bot_name}, ");
                 }
@@ -65,6 +72,7 @@ impl MainBot {
         match self.bots.active.remove(bot_name) {
             Some(_) => {
                 self.save_bots().expect("Failed to save state");
+                self.shared.lock().unwrap().command_names_outdated = true;
                 Some(format!("{bot_name} is now resting").into())
             }
             None => {
@@ -141,6 +149,295 @@ impl MainBot {
         }
     }
 
+    fn role_create(&mut self, role: &str, level: AuthorityLevel) -> Response {
+        let response = match self.roles.roles.insert(role.to_owned(), level) {
+            Some(old) => format!("Role {role} changed from {old:?} to {level:?}"),
+            None => format!("Created role {role} with {level:?} authority"),
+        };
+        self.roles.save().expect("Failed to save roles");
+        Some(response.into())
+    }
+
+    fn role_delete(&mut self, role: &str) -> Response {
+        if !self.roles.delete(role) {
+            return Some(format!("There is no role {role}").into());
+        }
+        self.roles.save().expect("Failed to save roles");
+        Some(format!("Deleted role {role}").into())
+    }
+
+    fn role_grant(&mut self, sender: &Sender, user_name: &str, role: &str) -> Response {
+        let level = match self.roles.roles.get(role) {
+            Some(&level) => level,
+            None => return Some(format!("There is no role {role}").into()),
+        };
+        // Nobody can grant more authority than they have
+        if level >= sender.roles.authority_level() {
+            return Some(format!("You are not allowed to grant {role}").into());
+        }
+        let user_id = match self.shared.lock().unwrap().users.find(user_name) {
+            Some(user) => user.id.clone(),
+            None => return Some(format!("I don't know {}", UserRef::parse(user_name)).into()),
+        };
+        if !self.roles.grant(&user_id, role) {
+            return Some(format!("{user_name} already has role {role}").into());
+        }
+        self.roles.save().expect("Failed to save roles");
+        Some(format!("{user_name} now has role {role}").into())
+    }
+
+    fn role_revoke(&mut self, sender: &Sender, user_name: &str, role: &str) -> Response {
+        let level = self
+            .roles
+            .roles
+            .get(role)
+            .copied()
+            .unwrap_or(AuthorityLevel::Viewer);
+        if level >= sender.roles.authority_level() {
+            return Some(format!("You are not allowed to revoke {role}").into());
+        }
+        let user_id = match self.shared.lock().unwrap().users.find(user_name) {
+            Some(user) => user.id.clone(),
+            None => return Some(format!("I don't know {}", UserRef::parse(user_name)).into()),
+        };
+        if !self.roles.revoke(&user_id, role) {
+            return Some(format!("{user_name} does not have role {role}").into());
+        }
+        self.roles.save().expect("Failed to save roles");
+        Some(format!("{user_name} no longer has role {role}").into())
+    }
+
+    fn role_list(&self) -> Response {
+        if self.roles.roles.is_empty() {
+            return Some("There are no custom roles".into());
+        }
+        let mut roles = self
+            .roles
+            .roles
+            .iter()
+            .map(|(role, level)| {
+                let users = self
+                    .roles
+                    .users
+                    .values()
+                    .filter(|roles| roles.contains(role))
+                    .count();
+                format!("{role} ({level:?}, {users} users)")
+            })
+            .collect::<Vec<_>>();
+        roles.sort();
+        Some(format!("Custom roles: {}", roles.join(", ")).into())
+    }
+
+    fn stats(&self, user_name: &str) -> Response {
+        let shared = self.shared.lock().unwrap();
+        match shared.users.find(user_name) {
+            Some(user) => Some(user.stats().into()),
+            None => Some(format!("I don't know {}", UserRef::parse(user_name)).into()),
+        }
+    }
+
+    fn set_prefix(&self, prefix: &str) -> Response {
+        let result = self
+            .shared
+            .lock()
+            .unwrap()
+            .aliases
+            .set_prefix(&self.channel, prefix);
+        if let Err(err) = result {
+            self.log(LogType::Error, &format!("Failed to save aliases: {err}"));
+            return Some("Failed to change the prefix".into());
+        }
+        Some(format!("Commands now start with {prefix}").into())
+    }
+
+    /// Commands of the main bot and of the active bots with the permission overrides applied
+    pub(super) fn all_commands(&self) -> Vec<(BotName, CommandInfo)> {
+        let shared = self.shared.lock().unwrap();
+        let own = command_infos(&self.commands)
+            .into_iter()
+            .map(|info| (Self::NAME.to_owned(), info));
+        let mut active = self.bots.active.iter().collect::<Vec<_>>();
+        active.sort_by_key(|(bot_name, _)| *bot_name);
+        let bots = active.into_iter().flat_map(|(bot_name, bot)| {
+            bot.help()
+                .into_iter()
+                .map(move |info| (bot_name.clone(), info))
+        });
+        own.chain(bots)
+            .map(|(bot_name, mut info)| {
+                if let Some(level) = shared
+                    .permissions
+                    .required_level(&self.channel, &info.usage)
+                {
+                    info.authority_level = level as usize;
+                }
+                (bot_name, info)
+            })
+            .collect()
+    }
+
+    fn help(&self, sender: &Sender, command: &str) -> Response {
+        let command = command_path(command);
+        let usages = self
+            .all_commands()
+            .into_iter()
+            .filter(|(_, info)| is_available(sender, info) && info.matches(&command))
+            .map(|(_, info)| info.usage)
+            .collect::<Vec<_>>();
+        if usages.is_empty() {
+            return Some(format!("I don't know the command {command}").into());
+        }
+        Some(format!("Usage: {}", usages.join(", ")).into())
+    }
+
+    fn command_disable(&self, path: &str, disabled: bool) -> Response {
+        let path = command_path(path);
+        let len = path.split_whitespace().count();
+        // Every spelling of the path, e.g. both `!queue` and `!list` for `!queue|!list`
+        let mut paths = BTreeSet::new();
+        for (_, info) in self.all_commands() {
+            if !info.matches(&path) {
+                continue;
+            }
+            let mut spellings = vec![String::new()];
+            for word in info.usage.split_whitespace().take(len) {
+                spellings = spellings
+                    .iter()
+                    .flat_map(|spelling| {
+                        word.split('|').map(move |alternative| {
+                            format!("{spelling} {alternative}").trim_start().to_owned()
+                        })
+                    })
+                    .collect();
+            }
+            paths.extend(spellings);
+        }
+        if paths.is_empty() {
+            return Some(format!("I don't know the command {path}").into());
+        }
+        if disabled
+            && paths
+                .iter()
+                .any(|path| path_matches(path, "!command enable"))
+        {
+            return Some("!command enable cannot be disabled".into());
+        }
+        let result = self.shared.lock().unwrap().permissions.set_disabled(
+            &self.channel,
+            paths.into_iter(),
+            disabled,
+        );
+        match result {
+            Ok(true) if disabled => Some(format!("{path} is now disabled").into()),
+            Ok(true) => Some(format!("{path} is now enabled").into()),
+            Ok(false) if disabled => Some(format!("{path} is already disabled").into()),
+            Ok(false) => Some(format!("{path} is not disabled").into()),
+            Err(err) => {
+                self.log(
+                    LogType::Error,
+                    &format!("Failed to save permissions: {err}"),
+                );
+                Some("Failed to save permissions".into())
+            }
+        }
+    }
+
+    /// Completes the command path in `!command disable|enable <path>`
+    pub(super) fn complete_path(
+        &self,
+        word: &str,
+        prompter: &Prompter,
+        start: usize,
+    ) -> Option<Vec<linefeed::Completion>> {
+        let mut words = prompter.buffer()[..start].split_whitespace();
+        if words.next() != Some("!command") || !matches!(words.next(), Some("disable" | "enable")) {
+            return None;
+        }
+        let typed = words.collect::<Vec<_>>();
+        let mut completions = self
+            .all_commands()
+            .into_iter()
+            .filter_map(|(_, info)| {
+                let mut usage = info.usage.split_whitespace();
+                for typed_word in &typed {
+                    let usage_word = usage.next()?;
+                    if !usage_word.split('|').any(|alt| alt == *typed_word) {
+                        return None;
+                    }
+                }
+                let next = usage.next()?;
+                Some(
+                    next.split('|')
+                        .filter(|alt| !alt.starts_with(['<', '[']) && alt.starts_with(word))
+                        .map(|alt| alt.to_owned())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+        completions.sort();
+        completions.dedup();
+        Some(
+            completions
+                .into_iter()
+                .map(linefeed::Completion::simple)
+                .collect(),
+        )
+    }
+
+    fn help_hint(&self) -> Response {
+        let shared = self.shared.lock().unwrap();
+        let prefix = shared.aliases.prefix(&self.channel);
+        Some(
+            format!(
+                "Type {prefix}commands to see the commands you can use, \
+                 and {prefix}help <command> to see how to use one"
+            )
+            .into(),
+        )
+    }
+
+    fn commands_list(&self, sender: &Sender) -> Response {
+        let mut names = self
+            .all_commands()
+            .into_iter()
+            .filter(|(_, info)| is_available(sender, info) && info.name().starts_with('!'))
+            .map(|(_, info)| info.name().to_owned())
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        Some(format!("Commands: {}", names.join(", ")).into())
+    }
+
+    /// Writes the reference of every command into a markdown file
+    fn commands_export(&self) -> std::io::Result<Response> {
+        let mut bots: Vec<(BotName, Vec<CommandInfo>)> = Vec::new();
+        for (bot_name, info) in self.all_commands() {
+            match bots.iter_mut().find(|(name, _)| *name == bot_name) {
+                Some((_, infos)) => infos.push(info),
+                None => bots.push((bot_name, vec![info])),
+            }
+        }
+
+        let mut markdown = String::from("# Commands\n");
+        for (bot_name, infos) in bots {
+            markdown += &format!("\n## {bot_name}\n\n");
+            for info in infos {
+                let level = match AuthorityLevel::from_level(info.authority_level) {
+                    AuthorityLevel::Viewer => String::new(),
+                    AuthorityLevel::Server => ". Console only.".to_owned(),
+                    level => format!(". {level:?} only."),
+                };
+                markdown += &format!("- `{}`{level}\n", info.usage);
+            }
+        }
+        std::fs::write(COMMANDS_EXPORT_FILE, markdown)?;
+        Ok(Some(
+            format!("Exported the commands into {COMMANDS_EXPORT_FILE}").into(),
+        ))
+    }
+
     pub fn commands(available_bots: impl IntoIterator<Item = BotName>) -> Commands<Self> {
         let backup_create = CommandBuilder::<Self>::new()
             .literal(["create"])
@@ -184,7 +481,122 @@ impl MainBot {
                 }),
             );
 
+        let role_create = TypedCommand::<Self>::new("!role create")
+            .word("role")
+            .choice(
+                "level",
+                [
+                    "viewer",
+                    "subscriber",
+                    "founder",
+                    "artist",
+                    "vip",
+                    "moderator",
+                    "broadcaster",
+                ],
+            )
+            .finalize(AuthorityLevel::Broadcaster as _, |bot, _, args| {
+                bot.role_create(args.text(0)?, args.text(1)?.parse().ok()?)
+            });
+
+        let role_delete = TypedCommand::<Self>::new("!role delete")
+            .word("role")
+            .finalize(AuthorityLevel::Broadcaster as _, |bot, _, args| {
+                bot.role_delete(args.text(0)?)
+            });
+
+        let role_grant = TypedCommand::<Self>::new("!role grant")
+            .user("user_name")
+            .word("role")
+            .finalize(AuthorityLevel::Moderator as _, |bot, sender, args| {
+                bot.role_grant(sender, &args.user(0)?.name, args.text(1)?)
+            });
+
+        let role_revoke = TypedCommand::<Self>::new("!role revoke")
+            .user("user_name")
+            .word("role")
+            .finalize(AuthorityLevel::Moderator as _, |bot, sender, args| {
+                bot.role_revoke(sender, &args.user(0)?.name, args.text(1)?)
+            });
+
+        let role_list = CommandBuilder::<Self>::new().literal(["list"]).finalize(
+            true,
+            AuthorityLevel::Moderator as _,
+            Arc::new(|bot, _, _| bot.role_list()),
+        );
+
+        let stats = TypedCommand::<Self>::new("!stats")
+            .user("user_name")
+            .optional()
+            .finalize(
+                AuthorityLevel::Viewer as _,
+                |bot, sender, args| match args.user(0) {
+                    Some(user) => bot.stats(&user.name),
+                    None => bot.stats(&sender.name),
+                },
+            );
+
+        let help = TypedCommand::<Self>::new("!help")
+            .line("command")
+            .optional()
+            .finalize(
+                AuthorityLevel::Viewer as _,
+                |bot, sender, args| match args.text(0) {
+                    Some(command) => bot.help(sender, command),
+                    None => bot.help_hint(),
+                },
+            );
+
+        let commands_list = CommandBuilder::<Self>::new().finalize(
+            true,
+            AuthorityLevel::Viewer as _,
+            Arc::new(|bot, sender, _| bot.commands_list(sender)),
+        );
+
+        let commands_export = CommandBuilder::<Self>::new().literal(["export"]).finalize(
+            true,
+            AuthorityLevel::Broadcaster as _,
+            Arc::new(|bot, _, _| match bot.commands_export() {
+                Ok(response) => response,
+                Err(err) => {
+                    bot.log(
+                        LogType::Error,
+                        &format!("Failed to export the commands: {err}"),
+                    );
+                    Some("Failed to export the commands".into())
+                }
+            }),
+        );
+
+        let prefix = TypedCommand::<Self>::new("!prefix")
+            .word("prefix")
+            .finalize(AuthorityLevel::Broadcaster as _, |bot, _, args| {
+                bot.set_prefix(args.text(0)?)
+            });
+
+        let command_disable = CommandBuilder::<Self>::new()
+            .literal(["!command"])
+            .choice(["disable", "enable"])
+            .line()
+            .finalize(
+                true,
+                AuthorityLevel::Moderator as _,
+                Arc::new(|bot, _, args| bot.command_disable(&args[1], args[0] == "disable")),
+            );
+
         Commands::new(vec![
+            help,
+            command_disable,
+            prefix,
+            CommandBuilder::new()
+                .literal(["!commands"])
+                .split([commands_list, commands_export]),
+            stats,
+            role_create,
+            role_delete,
+            role_grant,
+            role_revoke,
+            CommandBuilder::new().literal(["!role"]).split([role_list]),
             CommandBuilder::new()
                 .choice(["!enable", "!disable", "!reset"])
                 .choice(std::iter::once("all".to_owned()).chain(available_bots))
@@ -232,3 +644,20 @@ fn clear_dir(path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
     std::fs::create_dir_all(path)?;
     Ok(())
 }
+
+/// Command path with the default prefix, e.g. `!timer set` for `timer set`
+fn command_path(path: &str) -> String {
+    let path = path.split_whitespace().collect::<Vec<_>>().join(" ");
+    if path.starts_with('!') {
+        path
+    } else {
+        format!("!{path}")
+    }
+}
+
+/// Whether the sender is allowed to call the command
+fn is_available(sender: &Sender, info: &CommandInfo) -> bool {
+    sender
+        .roles
+        .satisfies(AuthorityLevel::from_level(info.authority_level))
+}
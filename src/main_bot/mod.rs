@@ -8,12 +8,14 @@ mod bot;
 mod bots;
 mod commands;
 mod mutex;
+mod roles;
 
 pub use bot::*;
 use bots::*;
 pub use mutex::*;
+pub use roles::*;
 
-type BotConstructor = fn(&Option<Cli>) -> Box<dyn Bot>;
+type BotConstructor = fn(&Option<Cli>, &Shared) -> Box<dyn Bot>;
 
 // -- Modify this section to include a new bot into the main bot --
 
@@ -25,6 +27,9 @@ pub enum SerializedBot {
     Timer(TimerSerialized),
     Vote(VoteSerialized),
     Gamejam(Box<GamejamSerialized>),
+    Mod(ModSerialized),
+    Announce(AnnounceSerialized),
+    Counter(CounterSerialized),
 }
 
 fn constructors() -> impl IntoIterator<Item = (BotName, BotConstructor)> {
@@ -36,6 +41,9 @@ fn constructors() -> impl IntoIterator<Item = (BotName, BotConstructor)> {
         (TimerBot::NAME.to_owned(), TimerBot::new_boxed as _),
         (VoteBot::NAME.to_owned(), VoteBot::new_boxed as _),
         (GamejamBot::NAME.to_owned(), GamejamBot::new_boxed as _),
+        (ModBot::NAME.to_owned(), ModBot::new_boxed as _),
+        (AnnounceBot::NAME.to_owned(), AnnounceBot::new_boxed as _),
+        (CounterBot::NAME.to_owned(), CounterBot::new_boxed as _),
     ]
 }
 
@@ -25,6 +25,9 @@ impl linefeed::Completer<linefeed::DefaultTerminal> for MutexBot {
         end: usize,
     ) -> Option<Vec<linefeed::Completion>> {
         let mut main = futures::executor::block_on(self.0.lock());
+        if let Some(completions) = main.complete_path(word, prompter, start) {
+            return Some(completions);
+        }
         let main_completetion = main.commands.complete(word, prompter, start, end);
         let bots = &mut main.bots;
 
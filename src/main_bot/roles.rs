@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use super::*;
+
+const ROLES_FILE: &str = "config/roles.json";
+
+/// Roles granted to users by moderators, on top of their twitch badges
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CustomRoles {
+    /// Authority level granted by each role
+    pub roles: HashMap<String, AuthorityLevel>,
+    /// Roles of each user, keyed by the twitch user id, that stays the same when the user is renamed
+    pub users: HashMap<String, HashSet<String>>,
+}
+
+impl CustomRoles {
+    pub fn load() -> std::io::Result<Self> {
+        match std::fs::File::open(ROLES_FILE) {
+            Ok(file) => Ok(serde_json::from_reader(std::io::BufReader::new(file))?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let file = std::io::BufWriter::new(std::fs::File::create(ROLES_FILE)?);
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+
+    /// Moves the roles saved by older versions under the lowercase user name to the user id.
+    /// Returns the names of the users, that are not in the registry, their roles are dropped,
+    /// or None if there is nothing to migrate.
+    pub fn migrate(&mut self, registry: &UserRegistry) -> Option<Vec<String>> {
+        let names = self
+            .users
+            .keys()
+            .filter(|key| !key.chars().all(|c| c.is_ascii_digit()))
+            .cloned()
+            .collect::<Vec<_>>();
+        if names.is_empty() {
+            return None;
+        }
+        let mut unknown = Vec::new();
+        for name in names {
+            let roles = self.users.remove(&name).unwrap_or_default();
+            match registry.find(&name) {
+                Some(user) => self.users.entry(user.id.clone()).or_default().extend(roles),
+                None => unknown.push(name),
+            }
+        }
+        Some(unknown)
+    }
+
+    /// Adds the custom roles of the sender to the message and raises its authority level
+    pub fn apply(&self, message: &mut CommandMessage) {
+        let user_roles = match message.sender.id.as_ref().and_then(|id| self.users.get(id)) {
+            Some(roles) => roles,
+            None => return,
+        };
+        let roles = &mut message.sender.roles;
+        for role in user_roles {
+            if let Some(&level) = self.roles.get(role) {
+                roles.custom.push(role.clone());
+                roles.levels.push(level);
+            }
+        }
+        message.authority_level = message
+            .authority_level
+            .max(roles.authority_level() as usize);
+    }
+
+    /// Returns false if the user already has the role
+    pub fn grant(&mut self, user_id: &str, role: &str) -> bool {
+        self.users
+            .entry(user_id.to_owned())
+            .or_default()
+            .insert(role.to_owned())
+    }
+
+    /// Returns false if the user did not have the role
+    pub fn revoke(&mut self, user_id: &str, role: &str) -> bool {
+        let removed = match self.users.get_mut(user_id) {
+            Some(roles) => roles.remove(role),
+            None => false,
+        };
+        if self
+            .users
+            .get(user_id)
+            .is_some_and(|roles| roles.is_empty())
+        {
+            self.users.remove(user_id);
+        }
+        removed
+    }
+
+    /// Removes the role from everyone
+    pub fn delete(&mut self, role: &str) -> bool {
+        self.users.values_mut().for_each(|roles| {
+            roles.remove(role);
+        });
+        self.users.retain(|_, roles| !roles.is_empty());
+        self.roles.remove(role).is_some()
+    }
+}
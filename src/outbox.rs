@@ -0,0 +1,335 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use super::*;
+
+/// Twitch rejects longer messages
+const MAX_MESSAGE_LENGTH: usize = 500;
+/// Twitch counts the messages sent during this period
+const RATE_LIMIT_PERIOD: Duration = Duration::from_secs(30);
+const RATE_LIMIT: usize = 20;
+const RATE_LIMIT_MODERATOR: usize = 100;
+const MAX_RETRIES: u32 = 3;
+
+/// Queue of the messages to be sent to twitch.
+/// The messages are sent one by one from a separate task,
+/// respecting the rate limits and the message length limit.
+#[derive(Clone)]
+pub struct Outbox {
+    sender: tokio::sync::mpsc::UnboundedSender<OutgoingMessage>,
+    is_moderator: Arc<AtomicBool>,
+}
+
+struct OutgoingMessage {
+    channel: ChannelLogin,
+    message: String,
+    target: Target,
+}
+
+/// Resolved delivery of the message
+#[derive(Debug, Clone)]
+enum Target {
+    Say,
+    Reply {
+        parent_id: String,
+    },
+    Action,
+    /// Sent through the twitch api, falls back to the reply, if there is a message to reply to
+    Whisper {
+        user_id: String,
+        parent_id: Option<String>,
+    },
+    /// Sent through the twitch api, falls back to a usual message
+    Announcement,
+}
+
+struct OutboxWorker {
+    cli: Option<Cli>,
+    client: TwitchClient,
+    helix: Option<Helix>,
+    receiver: tokio::sync::mpsc::UnboundedReceiver<OutgoingMessage>,
+    is_moderator: Arc<AtomicBool>,
+    /// Time of every message sent during the last rate limit period
+    sent: VecDeque<Instant>,
+    /// Last message sent to each channel, with its recipient
+    last_sent: HashMap<ChannelLogin, (Option<String>, String, Instant)>,
+}
+
+impl Outbox {
+    /// Creates the outbox and spawns the task sending the messages
+    pub fn new(cli: Option<Cli>, client: TwitchClient, helix: Option<Helix>) -> Self {
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        let is_moderator = Arc::new(AtomicBool::new(false));
+        let worker = OutboxWorker {
+            cli,
+            client,
+            helix,
+            receiver,
+            is_moderator: is_moderator.clone(),
+            sent: VecDeque::new(),
+            last_sent: HashMap::new(),
+        };
+        tokio::spawn(worker.run());
+        Self {
+            sender,
+            is_moderator,
+        }
+    }
+
+    pub fn send(&self, channel: ChannelLogin, message: String) {
+        self.push(OutgoingMessage {
+            channel,
+            message,
+            target: Target::Say,
+        });
+    }
+
+    /// Sends the response to the message from the sender, respecting its delivery
+    pub fn respond(&self, channel: ChannelLogin, sender: &Sender, response: ResponseMsg) {
+        let target = match response.delivery {
+            Delivery::Say => Target::Say,
+            Delivery::Reply => match &sender.message_id {
+                Some(parent_id) => Target::Reply {
+                    parent_id: parent_id.clone(),
+                },
+                None => Target::Say,
+            },
+            Delivery::Whisper => match &sender.id {
+                Some(user_id) => Target::Whisper {
+                    user_id: user_id.clone(),
+                    parent_id: sender.message_id.clone(),
+                },
+                None => Target::Say,
+            },
+            Delivery::Action => Target::Action,
+            Delivery::Announcement => Target::Announcement,
+        };
+        self.push(OutgoingMessage {
+            channel,
+            message: response.message,
+            target,
+        });
+    }
+
+    fn push(&self, message: OutgoingMessage) {
+        // Fails only if the worker has stopped, which happens on shutdown
+        let _ = self.sender.send(message);
+    }
+
+    /// Moderators are allowed to send more messages
+    pub fn set_moderator(&self, is_moderator: bool) {
+        self.is_moderator.store(is_moderator, Ordering::Relaxed);
+    }
+}
+
+impl OutboxWorker {
+    async fn run(mut self) {
+        while let Some(outgoing) = self.receiver.recv().await {
+            let max_length = MAX_MESSAGE_LENGTH - outgoing.target.wrap("").len();
+            for part in split_message(&outgoing.message, max_length) {
+                let part = outgoing.target.wrap(&part);
+                if self.is_duplicate(&outgoing.channel, &outgoing.target, &part) {
+                    log(
+                        &self.cli,
+                        LogType::Warn,
+                        &format!("Skipped a duplicate message: {part}"),
+                    );
+                    continue;
+                }
+                self.wait_rate_limit().await;
+                self.send(&outgoing.channel, &outgoing.target, part).await;
+            }
+        }
+    }
+
+    /// Twitch drops identical consecutive messages,
+    /// while the same reply to different users is not a duplicate
+    fn is_duplicate(&self, channel: &ChannelLogin, target: &Target, message: &str) -> bool {
+        self.last_sent
+            .get(channel)
+            .is_some_and(|(recipient, last, time)| {
+                recipient.as_deref() == target.recipient()
+                    && last == message
+                    && time.elapsed() < RATE_LIMIT_PERIOD
+            })
+    }
+
+    async fn wait_rate_limit(&mut self) {
+        let limit = if self.is_moderator.load(Ordering::Relaxed) {
+            RATE_LIMIT_MODERATOR
+        } else {
+            RATE_LIMIT
+        };
+        while let Some(&oldest) = self.sent.front() {
+            if oldest.elapsed() >= RATE_LIMIT_PERIOD {
+                self.sent.pop_front();
+            } else if self.sent.len() >= limit {
+                tokio::time::sleep(RATE_LIMIT_PERIOD.saturating_sub(oldest.elapsed())).await;
+            } else {
+                break;
+            }
+        }
+    }
+
+    async fn send(&mut self, channel: &ChannelLogin, target: &Target, message: String) {
+        log(
+            &self.cli,
+            LogType::Send,
+            &format!("{}: {}", channel, message),
+        );
+        let target = match self.send_helix(channel, target, &message).await {
+            Some(target) => target,
+            None => return,
+        };
+        let target = &target;
+        let mut retry_delay = Duration::from_secs(1);
+        for attempt in 1..=MAX_RETRIES {
+            let result = match target.reply_message(channel, &message) {
+                Some(reply) => self.client.send_message(reply).await,
+                None => self.client.say(channel.clone(), message.clone()).await,
+            };
+            match result {
+                Ok(()) => {
+                    let now = Instant::now();
+                    self.sent.push_back(now);
+                    let recipient = target.recipient().map(|recipient| recipient.to_owned());
+                    self.last_sent
+                        .insert(channel.clone(), (recipient, message, now));
+                    return;
+                }
+                Err(error) => {
+                    log(
+                        &self.cli,
+                        LogType::Error,
+                        &format!("Failed to send a message (attempt {attempt}): {error}"),
+                    );
+                    if attempt < MAX_RETRIES {
+                        tokio::time::sleep(retry_delay).await;
+                        retry_delay *= 2;
+                    }
+                }
+            }
+        }
+        log(
+            &self.cli,
+            LogType::Error,
+            &format!("Gave up sending a message: {message}"),
+        );
+    }
+
+    /// Sends whispers and announcements through the twitch api.
+    /// Returns the target in the chat, if the message still has to be sent there.
+    async fn send_helix(
+        &mut self,
+        channel: &ChannelLogin,
+        target: &Target,
+        message: &str,
+    ) -> Option<Target> {
+        let fallback = target.fallback()?;
+        let helix = match &mut self.helix {
+            Some(helix) => helix,
+            None => return Some(fallback),
+        };
+        let result = match target {
+            Target::Whisper { user_id, .. } => helix.whisper(user_id, message).await,
+            _ => helix.announce(channel, message).await,
+        };
+        match result {
+            Ok(()) => None,
+            Err(error) => {
+                log(
+                    &self.cli,
+                    LogType::Warn,
+                    &format!("Failed to send through the twitch api, sending to the chat: {error}"),
+                );
+                Some(fallback)
+            }
+        }
+    }
+}
+
+impl Target {
+    /// How the message is sent in the chat, if the twitch api is not available.
+    /// None if the message is always sent in the chat.
+    fn fallback(&self) -> Option<Target> {
+        match self {
+            Self::Whisper {
+                parent_id: Some(parent_id),
+                ..
+            } => Some(Self::Reply {
+                parent_id: parent_id.clone(),
+            }),
+            Self::Whisper { .. } | Self::Announcement => Some(Self::Say),
+            _ => None,
+        }
+    }
+
+    /// Formats the text of the message, so that twitch recognizes its type
+    fn wrap(&self, message: &str) -> String {
+        match self {
+            Self::Say | Self::Reply { .. } | Self::Whisper { .. } | Self::Announcement => {
+                message.to_owned()
+            }
+            Self::Action => format!("\u{1}ACTION {message}\u{1}"),
+        }
+    }
+
+    /// The user or the message the message is addressed to
+    fn recipient(&self) -> Option<&str> {
+        match self {
+            Self::Reply { parent_id } => Some(parent_id),
+            Self::Whisper { user_id, .. } => Some(user_id),
+            _ => None,
+        }
+    }
+
+    /// Replies are sent as raw irc messages with the parent message tag
+    fn reply_message(
+        &self,
+        channel: &ChannelLogin,
+        message: &str,
+    ) -> Option<twitch_irc::message::IRCMessage> {
+        match self {
+            Self::Reply { parent_id } => twitch_irc::message::IRCMessage::parse(&format!(
+                "@reply-parent-msg-id={parent_id} PRIVMSG #{channel} :{message}"
+            ))
+            .ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Splits the message into parts no longer than `max_length` bytes, preferring word boundaries
+fn split_message(message: &str, max_length: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    for word in message.split_whitespace() {
+        let mut word = word;
+        // Words that do not fit into a single message are cut
+        while word.len() > max_length {
+            if !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+            }
+            let mut cut = max_length;
+            while !word.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            parts.push(word[..cut].to_owned());
+            word = &word[cut..];
+        }
+        if !current.is_empty() && current.len() + 1 + word.len() > max_length {
+            parts.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
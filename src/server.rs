@@ -20,6 +20,18 @@ pub async fn get_state(bot: &BotState) -> Json<Vec<SerializedBot>> {
     Json(bot.serialize().collect())
 }
 
+#[get("/users")]
+pub async fn users(bot: &BotState) -> Json<Vec<UserRecord>> {
+    let bot = bot.lock().await;
+    Json(bot.users())
+}
+
+/// The queue exported by GamejamBot
+#[get("/queue")]
+pub async fn queue() -> Option<rocket::fs::NamedFile> {
+    rocket::fs::NamedFile::open("status/queue.html").await.ok()
+}
+
 #[get("/events")]
 pub fn events(bot: &BotState) -> EventStream![Event + '_] {
     EventStream! {
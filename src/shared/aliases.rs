@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use super::*;
+
+const ALIASES_FILE: &str = "config/aliases.json";
+
+/// Command prefix and aliases for each channel
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Aliases {
+    #[serde(flatten)]
+    pub channels: HashMap<ChannelLogin, ChannelAliases>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChannelAliases {
+    /// Prefix of the commands in the chat, `!` if not set
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Command paths replaced before the commands are matched, e.g. `!q` -> `!queue`
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+}
+
+impl Aliases {
+    pub fn load() -> std::io::Result<Self> {
+        match std::fs::File::open(ALIASES_FILE) {
+            Ok(file) => Ok(serde_json::from_reader(std::io::BufReader::new(file))?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let file = std::io::BufWriter::new(std::fs::File::create(ALIASES_FILE)?);
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+
+    /// Rewrites the message with the prefix and the aliases of the channel,
+    /// so that it can be matched against the command trees.
+    /// Returns None if the message uses `!` in a channel with a different prefix.
+    pub fn apply(
+        &self,
+        channel: &ChannelLogin,
+        message: &CommandMessage,
+    ) -> Option<CommandMessage> {
+        let mut result = CommandMessage {
+            sender: message.sender.clone(),
+            message_text: message.message_text.clone(),
+            authority_level: message.authority_level,
+        };
+        let aliases = match self.channels.get(channel) {
+            Some(aliases) => aliases,
+            None => return Some(result),
+        };
+
+        // The console always uses the default prefix
+        if let (Some(prefix), MessageOrigin::Twitch) = (&aliases.prefix, &message.sender.origin) {
+            if prefix != "!" {
+                if let Some(command) = message.message_text.strip_prefix(prefix.as_str()) {
+                    result.message_text = format!("!{command}");
+                } else if message.message_text.starts_with('!') {
+                    return None;
+                }
+            }
+        }
+
+        if let Some(alias) = longest_match(aliases.aliases.keys(), &result.message_text) {
+            let mut rest = result.message_text.as_str();
+            for _ in alias.split_whitespace() {
+                rest = rest.trim_start();
+                rest = rest
+                    .find(char::is_whitespace)
+                    .map_or("", |end| &rest[end..]);
+            }
+            result.message_text = format!("{}{}", aliases.aliases[alias], rest);
+        }
+        Some(result)
+    }
+
+    /// The prefix of the commands in the channel
+    pub fn prefix(&self, channel: &ChannelLogin) -> &str {
+        self.channels
+            .get(channel)
+            .and_then(|aliases| aliases.prefix.as_deref())
+            .unwrap_or("!")
+    }
+
+    pub fn set_prefix(&mut self, channel: &ChannelLogin, prefix: &str) -> std::io::Result<()> {
+        let aliases = self.channels.entry(channel.clone()).or_default();
+        aliases.prefix = if prefix == "!" {
+            None
+        } else {
+            Some(prefix.to_owned())
+        };
+        self.save()
+    }
+
+    pub fn add(
+        &mut self,
+        channel: &ChannelLogin,
+        alias: &str,
+        command: &str,
+    ) -> std::io::Result<()> {
+        self.channels
+            .entry(channel.clone())
+            .or_default()
+            .aliases
+            .insert(alias.to_owned(), command.to_owned());
+        self.save()
+    }
+
+    /// Removes the alias and returns the command it was replaced with
+    pub fn remove(
+        &mut self,
+        channel: &ChannelLogin,
+        alias: &str,
+    ) -> std::io::Result<Option<String>> {
+        let command = self
+            .channels
+            .get_mut(channel)
+            .and_then(|aliases| aliases.aliases.remove(alias));
+        if command.is_some() {
+            self.save()?;
+        }
+        Ok(command)
+    }
+}
@@ -0,0 +1,224 @@
+use std::{collections::HashMap, time::Instant};
+
+use super::*;
+
+const COOLDOWNS_FILE: &str = "config/cooldowns.json";
+/// Longest cooldown in seconds, one day
+pub const MAX_COOLDOWN: f32 = 86400.0;
+
+/// Limits how often a command can be called
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandCooldown {
+    /// Command paths sharing the cooldown, e.g. `!queue` and `!list`
+    pub paths: Vec<String>,
+    /// Seconds between any two calls
+    #[serde(default)]
+    pub global: Option<f32>,
+    /// Seconds between two calls in the same channel
+    #[serde(default)]
+    pub channel: Option<f32>,
+    /// Seconds between two calls by the same user
+    #[serde(default)]
+    pub user: Option<f32>,
+    /// Users with this authority level or higher ignore the cooldown
+    #[serde(default = "default_exempt")]
+    pub exempt: AuthorityLevel,
+    /// Whether to ignore calls on cooldown without telling the user.
+    /// Otherwise the user is told once per cooldown, further calls are ignored.
+    #[serde(default)]
+    pub silent: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct Cooldowns {
+    /// Overrides of the cooldowns declared by the bots
+    pub config: Vec<CommandCooldown>,
+    last_used: HashMap<(String, CooldownScope), Instant>,
+    /// Until when the user has been told that the command is on cooldown
+    notified: HashMap<(String, String), Instant>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum CooldownScope {
+    Global,
+    Channel(ChannelLogin),
+    User(String),
+}
+
+fn default_exempt() -> AuthorityLevel {
+    AuthorityLevel::Moderator
+}
+
+impl CommandCooldown {
+    pub fn new<T: ToString>(paths: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            paths: paths.into_iter().map(|path| path.to_string()).collect(),
+            global: None,
+            channel: None,
+            user: None,
+            exempt: default_exempt(),
+            silent: false,
+        }
+    }
+
+    pub fn global(self, seconds: f32) -> Self {
+        Self {
+            global: Some(seconds),
+            ..self
+        }
+    }
+
+    pub fn channel(self, seconds: f32) -> Self {
+        Self {
+            channel: Some(seconds),
+            ..self
+        }
+    }
+
+    pub fn user(self, seconds: f32) -> Self {
+        Self {
+            user: Some(seconds),
+            ..self
+        }
+    }
+
+    pub fn silent(self) -> Self {
+        Self {
+            silent: true,
+            ..self
+        }
+    }
+
+    /// Returns the length of the longest path matching the message
+    fn matches(&self, message_text: &str) -> Option<usize> {
+        self.paths
+            .iter()
+            .filter(|path| path_matches(path, message_text))
+            .map(|path| path.split_whitespace().count())
+            .max()
+    }
+
+    /// Checks that every cooldown is a number of seconds from 0 to [MAX_COOLDOWN]
+    pub fn validate(&self) -> Result<(), String> {
+        for seconds in [self.global, self.channel, self.user].into_iter().flatten() {
+            check_cooldown(seconds)?;
+        }
+        Ok(())
+    }
+
+    fn key(&self) -> String {
+        self.paths.first().cloned().unwrap_or_default()
+    }
+
+    fn scopes(&self, channel: &ChannelLogin, sender: &Sender) -> Vec<(CooldownScope, f32)> {
+        [
+            (CooldownScope::Global, self.global),
+            (CooldownScope::Channel(channel.clone()), self.channel),
+            (CooldownScope::User(sender.login.clone()), self.user),
+        ]
+        .into_iter()
+        .filter_map(|(scope, seconds)| seconds.map(|seconds| (scope, seconds)))
+        .collect()
+    }
+}
+
+impl Cooldowns {
+    pub fn load() -> std::io::Result<Self> {
+        let config: Vec<CommandCooldown> = match std::fs::File::open(COOLDOWNS_FILE) {
+            Ok(file) => serde_json::from_reader(std::io::BufReader::new(file))?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        for cooldown in &config {
+            cooldown.validate().map_err(|error| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "Invalid cooldown of {} in {COOLDOWNS_FILE}: {error}",
+                        cooldown.key()
+                    ),
+                )
+            })?;
+        }
+        Ok(Self {
+            config,
+            last_used: HashMap::new(),
+            notified: HashMap::new(),
+        })
+    }
+
+    /// Finds the cooldown for the message, preferring the config over the declared cooldowns
+    pub fn find(
+        &self,
+        declared: &[CommandCooldown],
+        message_text: &str,
+    ) -> Option<CommandCooldown> {
+        let longest = |cooldowns: &[CommandCooldown]| {
+            cooldowns
+                .iter()
+                .filter_map(|cooldown| cooldown.matches(message_text).map(|len| (len, cooldown)))
+                .max_by_key(|(len, _)| *len)
+                .map(|(_, cooldown)| cooldown.clone())
+        };
+        longest(&self.config).or_else(|| longest(declared))
+    }
+
+    /// Returns the number of seconds left until the command can be called again
+    pub fn remaining(
+        &self,
+        cooldown: &CommandCooldown,
+        channel: &ChannelLogin,
+        sender: &Sender,
+    ) -> Option<f32> {
+        let key = cooldown.key();
+        cooldown
+            .scopes(channel, sender)
+            .into_iter()
+            .filter_map(|(scope, seconds)| {
+                let elapsed = self.last_used.get(&(key.clone(), scope))?.elapsed();
+                let remaining = seconds - elapsed.as_secs_f32();
+                (remaining > 0.0).then_some(remaining)
+            })
+            .max_by(|a, b| a.total_cmp(b))
+    }
+
+    pub fn trigger(&mut self, cooldown: &CommandCooldown, channel: &ChannelLogin, sender: &Sender) {
+        let key = cooldown.key();
+        let now = Instant::now();
+        for (scope, _) in cooldown.scopes(channel, sender) {
+            self.last_used.insert((key.clone(), scope), now);
+        }
+    }
+
+    /// Checks whether the user should be told that the command is on cooldown,
+    /// which happens once until the cooldown ends
+    pub fn notify(&mut self, cooldown: &CommandCooldown, sender: &Sender, remaining: f32) -> bool {
+        let now = Instant::now();
+        self.notified.retain(|_, until| *until > now);
+        let key = (cooldown.key(), sender.login.clone());
+        if self.notified.contains_key(&key) {
+            return false;
+        }
+        // Broken cooldowns are not worth a notice
+        let until = std::time::Duration::try_from_secs_f32(remaining)
+            .ok()
+            .and_then(|remaining| now.checked_add(remaining));
+        match until {
+            Some(until) => {
+                self.notified.insert(key, until);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Checks that the cooldown is a number of seconds from 0 to [MAX_COOLDOWN]
+pub fn check_cooldown(seconds: f32) -> Result<f32, String> {
+    if !(0.0..=MAX_COOLDOWN).contains(&seconds) {
+        return Err(format!(
+            "The cooldown should be from 0 to {MAX_COOLDOWN} seconds"
+        ));
+    }
+    Ok(seconds)
+}
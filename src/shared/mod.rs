@@ -0,0 +1,43 @@
+use super::*;
+
+mod aliases;
+mod cooldowns;
+mod permissions;
+mod users;
+mod variables;
+
+pub use aliases::*;
+pub use cooldowns::*;
+pub use permissions::*;
+pub use users::*;
+pub use variables::*;
+
+/// State shared between all bots
+pub type Shared = Arc<std::sync::Mutex<SharedState>>;
+
+pub struct SharedState {
+    pub aliases: Aliases,
+    pub permissions: Permissions,
+    pub cooldowns: Cooldowns,
+    pub users: UserRegistry,
+    pub variables: Variables,
+    /// First words of the commands of the main bot and the active bots, e.g. `!queue` and `!list`
+    pub command_names: HashSet<String>,
+    /// Set when the bots add or remove commands,
+    /// so that the main bot refreshes the names before the next message
+    pub command_names_outdated: bool,
+}
+
+impl SharedState {
+    pub fn load() -> std::io::Result<Self> {
+        Ok(Self {
+            aliases: Aliases::load()?,
+            permissions: Permissions::load()?,
+            cooldowns: Cooldowns::load()?,
+            users: UserRegistry::load()?,
+            variables: Variables::default(),
+            command_names: HashSet::new(),
+            command_names_outdated: true,
+        })
+    }
+}
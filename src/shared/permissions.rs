@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+use super::*;
+
+const PERMISSIONS_FILE: &str = "config/permissions.json";
+
+/// Overrides of the command permissions for each channel
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Permissions {
+    #[serde(flatten)]
+    pub channels: HashMap<ChannelLogin, ChannelPermissions>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChannelPermissions {
+    /// Minimum authority level for the command path, e.g. `!timer set`
+    #[serde(default)]
+    pub commands: HashMap<String, AuthorityLevel>,
+    /// Users that can call the command path regardless of their authority
+    #[serde(default)]
+    pub allow: HashMap<String, HashSet<String>>,
+    /// Users that can never call the command path
+    #[serde(default)]
+    pub deny: HashMap<String, HashSet<String>>,
+    /// Command paths that nobody can call from the chat
+    #[serde(default)]
+    pub disabled: HashSet<String>,
+}
+
+impl Permissions {
+    pub fn load() -> std::io::Result<Self> {
+        match std::fs::File::open(PERMISSIONS_FILE) {
+            Ok(file) => Ok(serde_json::from_reader(std::io::BufReader::new(file))?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let file = std::io::BufWriter::new(std::fs::File::create(PERMISSIONS_FILE)?);
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+
+    /// Checks whether the message calls a disabled command.
+    /// The console can call disabled commands.
+    pub fn is_disabled(&self, channel: &ChannelLogin, message: &CommandMessage) -> bool {
+        if message.sender.roles.satisfies(AuthorityLevel::Server) {
+            return false;
+        }
+        self.channels.get(channel).is_some_and(|permissions| {
+            longest_match(&permissions.disabled, &message.message_text).is_some()
+        })
+    }
+
+    /// Disables or enables the command paths, that are the spellings of the same command.
+    /// Returns false if they are already in that state.
+    pub fn set_disabled(
+        &mut self,
+        channel: &ChannelLogin,
+        paths: impl IntoIterator<Item = String>,
+        disabled: bool,
+    ) -> std::io::Result<bool> {
+        let permissions = self.channels.entry(channel.clone()).or_default();
+        let mut changed = false;
+        for path in paths {
+            changed |= if disabled {
+                permissions.disabled.insert(path)
+            } else {
+                permissions.disabled.remove(&path)
+            };
+        }
+        if changed {
+            self.save()?;
+        }
+        Ok(changed)
+    }
+
+    /// Checks whether the sender is on the deny list of a path, that the message starts with
+    pub fn is_denied(&self, channel: &ChannelLogin, message: &CommandMessage) -> bool {
+        if message.sender.roles.satisfies(AuthorityLevel::Server) {
+            return false;
+        }
+        self.channels.get(channel).is_some_and(|permissions| {
+            permissions.deny.iter().any(|(path, users)| {
+                path_matches(path, &message.message_text) && users.contains(&message.sender.login)
+            })
+        })
+    }
+
+    /// Checks whether the sender may call the matched command.
+    /// The overrides and the allow lists change the level required by the command
+    /// with exactly that path, and never make console only commands available from the chat.
+    pub fn allows(&self, channel: &ChannelLogin, sender: &Sender, command: &CommandMatch) -> bool {
+        let required = AuthorityLevel::from_level(command.authority_level);
+        if required == AuthorityLevel::Server {
+            return sender.roles.satisfies(required);
+        }
+        let permissions = match self.channels.get(channel) {
+            Some(permissions) => permissions,
+            None => return sender.roles.satisfies(required),
+        };
+        let is_allowed = permissions
+            .allow
+            .get(&command.path)
+            .is_some_and(|users| users.contains(&sender.login));
+        let required = permissions
+            .commands
+            .get(&command.path)
+            .copied()
+            .unwrap_or(required);
+        is_allowed || sender.roles.satisfies(required)
+    }
+
+    /// Minimum authority level of the command, if it is overridden in the channel.
+    /// Alternatives in the usage, like `!queue|!list`, are checked by the first one.
+    pub fn required_level(&self, channel: &ChannelLogin, usage: &str) -> Option<AuthorityLevel> {
+        let permissions = self.channels.get(channel)?;
+        let path = usage
+            .split_whitespace()
+            .take_while(|word| !word.starts_with(['<', '[']))
+            .map(|word| word.split('|').next().unwrap_or(word))
+            .collect::<Vec<_>>()
+            .join(" ");
+        permissions.commands.get(&path).copied()
+    }
+}
+
+/// Finds the longest command path, which words are the first words of the message
+pub fn longest_match<'a>(
+    paths: impl IntoIterator<Item = &'a String>,
+    message_text: &str,
+) -> Option<&'a String> {
+    paths
+        .into_iter()
+        .filter(|path| path_matches(path, message_text))
+        .max_by_key(|path| path.split_whitespace().count())
+}
+
+pub fn path_matches(path: &str, message_text: &str) -> bool {
+    let mut words = message_text.split_whitespace();
+    path.split_whitespace()
+        .all(|path_word| words.next() == Some(path_word))
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use super::*;
+
+const USERS_FILE: &str = "config/users.json";
+/// How often the registry is saved, if it has changed
+const SAVE_INTERVAL: f32 = 30.0;
+
+/// Everyone who has written in the chat, keyed by the twitch user id
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UserRegistry {
+    pub users: HashMap<String, UserRecord>,
+    #[serde(skip)]
+    changed: bool,
+    #[serde(skip)]
+    save_timer: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserRecord {
+    pub id: String,
+    pub login: String,
+    pub name: String,
+    pub color: Option<Color>,
+    pub first_seen: u64,
+    pub last_seen: u64,
+    pub message_count: u64,
+    #[serde(default)]
+    pub roles: UserRoles,
+    /// Links to the games submitted to GamejamBot
+    #[serde(default)]
+    pub submitted_games: Vec<String>,
+    /// Links to the submitted games, that have been played
+    #[serde(default)]
+    pub played_games: Vec<String>,
+    #[serde(default)]
+    pub luck: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl UserRegistry {
+    pub fn load() -> std::io::Result<Self> {
+        match std::fs::File::open(USERS_FILE) {
+            Ok(file) => Ok(serde_json::from_reader(std::io::BufReader::new(file))?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&mut self) -> std::io::Result<()> {
+        let file = std::io::BufWriter::new(std::fs::File::create(USERS_FILE)?);
+        serde_json::to_writer(file, self)?;
+        self.changed = false;
+        Ok(())
+    }
+
+    /// Saves the registry from time to time, if it has changed
+    pub fn update(&mut self, delta_time: f32) -> std::io::Result<()> {
+        self.save_timer -= delta_time;
+        if self.changed && self.save_timer <= 0.0 {
+            self.save_timer = SAVE_INTERVAL;
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// Registers a chat message from the user
+    pub fn record_message(
+        &mut self,
+        user: &twitch_irc::message::TwitchUserBasics,
+        color: Option<Color>,
+        roles: &UserRoles,
+    ) {
+        let time = unix_time();
+        let record = self
+            .users
+            .entry(user.id.clone())
+            .or_insert_with(|| UserRecord {
+                id: user.id.clone(),
+                login: user.login.clone(),
+                name: user.name.clone(),
+                color: None,
+                first_seen: time,
+                last_seen: time,
+                message_count: 0,
+                roles: UserRoles::default(),
+                submitted_games: Vec::new(),
+                played_games: Vec::new(),
+                luck: None,
+            });
+        // The name might have changed
+        record.login = user.login.clone();
+        record.name = user.name.clone();
+        record.color = color;
+        record.last_seen = time;
+        record.message_count += 1;
+        record.roles = roles.clone();
+        self.changed = true;
+    }
+
+    /// Finds the user by name, ignoring the case and the `@` prefix
+    pub fn find(&self, name: &str) -> Option<&UserRecord> {
+        let user = UserRef::parse(name);
+        self.users
+            .values()
+            .find(|record| record.is_named(&user.name))
+    }
+
+    /// Finds the user by name, ignoring the case and the `@` prefix
+    pub fn find_mut(&mut self, name: &str) -> Option<&mut UserRecord> {
+        let user = UserRef::parse(name);
+        self.changed = true;
+        self.users
+            .values_mut()
+            .find(|record| record.is_named(&user.name))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut UserRecord> {
+        self.changed = true;
+        self.users.values_mut()
+    }
+}
+
+impl UserRecord {
+    pub fn user(&self) -> UserRef {
+        UserRef {
+            id: Some(self.id.clone()),
+            name: self.name.clone(),
+        }
+    }
+
+    /// Checks both the login and the display name, which may differ for localized names
+    pub fn is_named(&self, name: &str) -> bool {
+        same_name(&self.login, name) || same_name(&self.name, name)
+    }
+
+    pub fn stats(&self) -> String {
+        let now = unix_time();
+        let mut stats = format!(
+            "{}: first seen {} ago, last seen {} ago, {} messages",
+            self.name,
+            format_duration(now.saturating_sub(self.first_seen)),
+            format_duration(now.saturating_sub(self.last_seen)),
+            self.message_count,
+        );
+        if let Some(subscription) = &self.roles.subscription {
+            stats += &format!(
+                ", subscribed for {} months (tier {})",
+                subscription.months, subscription.tier
+            );
+        }
+        if !self.roles.custom.is_empty() {
+            stats += &format!(", roles: {}", self.roles.custom.join(", "));
+        }
+        if !self.submitted_games.is_empty() {
+            stats += &format!(
+                ", submitted {} games, {} played",
+                self.submitted_games.len(),
+                self.played_games.len()
+            );
+        }
+        if let Some(luck) = self.luck {
+            stats += &format!(", luck {}", luck);
+        }
+        stats
+    }
+}
+
+/// Formats the duration in the largest fitting unit, e.g. `3 days`
+fn format_duration(seconds: u64) -> String {
+    let (value, unit) = match seconds {
+        0..=59 => (seconds, "second"),
+        60..=3599 => (seconds / 60, "minute"),
+        3600..=86399 => (seconds / 3600, "hour"),
+        _ => (seconds / 86400, "day"),
+    };
+    if value == 1 {
+        format!("{} {}", value, unit)
+    } else {
+        format!("{} {}s", value, unit)
+    }
+}
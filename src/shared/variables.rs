@@ -0,0 +1,39 @@
+use std::{collections::HashMap, time::Instant};
+
+use super::*;
+
+/// Values provided by the bots for the templates of the custom commands, e.g. `{timer}`
+#[derive(Debug)]
+pub struct Variables {
+    values: HashMap<String, String>,
+    started: Instant,
+}
+
+impl Default for Variables {
+    fn default() -> Self {
+        Self {
+            values: HashMap::new(),
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Variables {
+    /// Registers the variable or updates its value
+    pub fn set(&mut self, name: &str, value: impl ToString) {
+        self.values.insert(name.to_owned(), value.to_string());
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(|value| value.as_str())
+    }
+
+    pub fn is_registered(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    /// Time since the bot has started
+    pub fn uptime(&self) -> std::time::Duration {
+        self.started.elapsed()
+    }
+}
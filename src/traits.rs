@@ -4,12 +4,30 @@ use super::*;
 pub trait Bot: Send {
     async fn handle_message(
         &mut self,
-        client: &TwitchClient,
+        shared: &Shared,
+        outbox: &Outbox,
         channel: &ChannelLogin,
         message: &CommandMessage,
     );
 
-    async fn update(&mut self, client: &TwitchClient, channel: &ChannelLogin, delta_time: f32) {
+    async fn update(
+        &mut self,
+        shared: &Shared,
+        outbox: &Outbox,
+        channel: &ChannelLogin,
+        delta_time: f32,
+    ) {
+        #![allow(unused_variables)]
+    }
+
+    /// Called on subscriptions, raids, cheers and channel points redemptions
+    async fn handle_event(
+        &mut self,
+        shared: &Shared,
+        outbox: &Outbox,
+        channel: &ChannelLogin,
+        event: &ChatEvent,
+    ) {
         #![allow(unused_variables)]
     }
 
@@ -21,6 +39,9 @@ pub trait Bot: Send {
         end: usize,
     ) -> Option<Vec<linefeed::Completion>>;
 
+    /// Describes the commands of the bot, used in `!help` and `!commands`
+    fn help(&self) -> Vec<CommandInfo>;
+
     fn serialize(&self) -> SerializedBot;
 }
 
@@ -30,26 +51,103 @@ pub trait BotPerformer {
 
     fn commands(&self) -> &Commands<Self>;
 
+    /// Cooldowns of the commands, can be overridden in the config
+    fn cooldowns(&self) -> Vec<CommandCooldown> {
+        Vec::new()
+    }
+
     async fn perform(
         &mut self,
         cli: &Option<Cli>,
-        client: &TwitchClient,
+        shared: &Shared,
+        outbox: &Outbox,
         channel: &ChannelLogin,
         message: &CommandMessage,
     ) {
-        let message_origin = &message.sender.origin;
+        let message = match shared.lock().unwrap().aliases.apply(channel, message) {
+            Some(message) => message,
+            None => return,
+        };
+        if shared
+            .lock()
+            .unwrap()
+            .permissions
+            .is_disabled(channel, &message)
+        {
+            return;
+        }
+        if shared
+            .lock()
+            .unwrap()
+            .permissions
+            .is_denied(channel, &message)
+        {
+            return;
+        }
+        let cooldown = shared
+            .lock()
+            .unwrap()
+            .cooldowns
+            .find(&self.cooldowns(), &message.message_text)
+            .filter(|cooldown| !message.sender.roles.satisfies(cooldown.exempt));
+
+        // Badge levels are not a ladder and the overrides change the levels of single commands,
+        // so the command tree is given the level, at which it calls exactly the allowed commands
         let commands = self.commands();
-        let matched = commands.find_commands(message).collect::<Vec<_>>();
-        for (command, args) in matched {
-            if let Some(mut response) = command(self, &message.sender, args) {
-                if let MessageOrigin::Twitch = message_origin {
-                    response.send_to_twitch = true;
-                }
-                if response.send_to_twitch {
-                    send_message(cli, client, channel.clone(), response.message).await;
-                } else {
-                    log(cli, LogType::Console, &response.message);
+        let level = {
+            let shared = shared.lock().unwrap();
+            authorized_level(
+                &match_commands(commands, &message.message_text),
+                |command| shared.permissions.allows(channel, &message.sender, command),
+            )
+        };
+        let message = match level {
+            Some(level) => CommandMessage {
+                authority_level: level,
+                ..message
+            },
+            None => return,
+        };
+        let matched = commands.find_commands(&message).collect::<Vec<_>>();
+        if matched.is_empty() {
+            return;
+        }
+
+        if let Some(cooldown) = &cooldown {
+            let remaining =
+                shared
+                    .lock()
+                    .unwrap()
+                    .cooldowns
+                    .remaining(cooldown, channel, &message.sender);
+            if let Some(remaining) = remaining {
+                let notify = !cooldown.silent
+                    && shared.lock().unwrap().cooldowns.notify(
+                        cooldown,
+                        &message.sender,
+                        remaining,
+                    );
+                if notify {
+                    let response = ResponseMsg::new(format!(
+                        "{} is on cooldown, wait {}s",
+                        cooldown.paths[0],
+                        remaining.ceil()
+                    ))
+                    .with_delivery(Delivery::Reply);
+                    send_response(cli, outbox, channel, &message.sender, response);
                 }
+                return;
+            }
+            shared
+                .lock()
+                .unwrap()
+                .cooldowns
+                .trigger(cooldown, channel, &message.sender);
+        }
+
+        for (command, args) in matched {
+            if let Some(response) = command(self, &message.sender, args) {
+                send_response(cli, outbox, channel, &message.sender, response);
             }
         }
     }
@@ -60,3 +158,39 @@ pub trait BotPerformer {
         std::fs::write(path, status_text).expect("Could not update bot status");
     }
 }
+
+/// Highest level, at which no matched command that is not allowed would be called.
+/// Returns None if none of the matched commands are allowed.
+fn authorized_level(
+    matches: &[CommandMatch],
+    is_allowed: impl Fn(&CommandMatch) -> bool,
+) -> Option<usize> {
+    let (allowed, denied): (Vec<_>, Vec<_>) =
+        matches.iter().partition(|command| is_allowed(command));
+    let level = allowed
+        .iter()
+        .map(|command| command.authority_level)
+        .max()?;
+    match denied.iter().map(|command| command.authority_level).min() {
+        Some(denied) if denied <= level => denied.checked_sub(1),
+        _ => Some(level),
+    }
+}
+
+/// Sends the response to twitch, or logs it into the console
+fn send_response(
+    cli: &Option<Cli>,
+    outbox: &Outbox,
+    channel: &ChannelLogin,
+    sender: &Sender,
+    mut response: ResponseMsg,
+) {
+    if let MessageOrigin::Twitch = sender.origin {
+        response.send_to_twitch = true;
+    }
+    if response.send_to_twitch {
+        outbox.respond(channel.clone(), sender, response);
+    } else {
+        log(cli, LogType::Console, &response.message);
+    }
+}